@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use geng::prelude::*;
+
+use crate::conversions::Aabb2RealConversions;
+
+/// Location of a single image inside of an [Atlas].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtlasRegion {
+    /// Pixel rect in texture coordinates, with the origin in the bottom-left corner.
+    /// Can be used with [DrawTexture::source_rect](crate::texture::DrawTexture::source_rect).
+    pub pixels: Aabb2<usize>,
+    /// Uv coordinates of the image inside the atlas.
+    pub uv: Aabb2<f32>,
+}
+
+/// A set of images packed into a single image.
+pub struct Atlas {
+    pub image: image::RgbaImage,
+    pub regions: HashMap<String, AtlasRegion>,
+}
+
+impl Atlas {
+    /// Size of the atlas in pixels.
+    pub fn size(&self) -> vec2<usize> {
+        vec2(self.image.width() as usize, self.image.height() as usize)
+    }
+
+    /// Get the location of the image with the given name.
+    pub fn get(&self, name: &str) -> Option<AtlasRegion> {
+        self.regions.get(name).copied()
+    }
+
+    /// Upload the atlas image into a texture.
+    pub fn texture(&self, ugli: &Ugli, options: &geng::asset::TextureOptions) -> ugli::Texture {
+        crate::texture::texture_from_image(ugli, self.image.clone(), options)
+    }
+}
+
+/// Packs images into an [Atlas] using the shelf packing algorithm.
+#[derive(Default)]
+pub struct AtlasBuilder {
+    images: Vec<(String, image::RgbaImage)>,
+    /// Transparent pixels between images.
+    pub padding: u32,
+    /// Number of pixels to extend each image's edges by to avoid bleeding when filtering.
+    pub extrude: u32,
+}
+
+impl AtlasBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_padding(self, padding: u32) -> Self {
+        Self { padding, ..self }
+    }
+
+    pub fn with_extrude(self, extrude: u32) -> Self {
+        Self { extrude, ..self }
+    }
+
+    /// Add an image to the atlas. Images with the same name replace the previous ones.
+    pub fn add(&mut self, name: impl Into<String>, image: image::RgbaImage) {
+        let name = name.into();
+        self.images.retain(|(other, _)| *other != name);
+        self.images.push((name, image));
+    }
+
+    pub fn build(self) -> Atlas {
+        let border = self.extrude;
+        let slots: Vec<vec2<u32>> = self
+            .images
+            .iter()
+            .map(|(_, image)| {
+                let size = vec2(image.width(), image.height());
+                size + vec2::splat(border * 2 + self.padding)
+            })
+            .collect();
+        let (size, positions) = pack(&slots, self.padding);
+
+        let mut atlas = image::RgbaImage::new(size.x, size.y);
+        let mut regions = HashMap::with_capacity(self.images.len());
+        for ((name, image), pos) in self.images.into_iter().zip(positions) {
+            regions.insert(name, place(&mut atlas, &image, pos, border));
+        }
+
+        Atlas {
+            image: atlas,
+            regions,
+        }
+    }
+}
+
+/// Copies the image into the atlas with its edges extruded by `extrude` pixels,
+/// `pos` being the top-left corner of the extruded image.
+/// Returns the region of the image without the extruded edges.
+pub(crate) fn place(
+    atlas: &mut image::RgbaImage,
+    image: &image::RgbaImage,
+    pos: vec2<u32>,
+    extrude: u32,
+) -> AtlasRegion {
+    let (width, height) = image.dimensions();
+    if width > 0 && height > 0 {
+        // Copy the image with its edges extruded
+        let border = extrude as i64;
+        for dy in -border..height as i64 + border {
+            for dx in -border..width as i64 + border {
+                let source_x = dx.clamp(0, width as i64 - 1) as u32;
+                let source_y = dy.clamp(0, height as i64 - 1) as u32;
+                let pixel = *image.get_pixel(source_x, source_y);
+                let x = (pos.x as i64 + border + dx) as u32;
+                let y = (pos.y as i64 + border + dy) as u32;
+                atlas.put_pixel(x, y, pixel);
+            }
+        }
+    }
+
+    // Flip into texture coordinates
+    let size = vec2(atlas.width(), atlas.height());
+    let min = vec2(pos.x + extrude, size.y - (pos.y + extrude + height));
+    let pixels = Aabb2::point(min.map(|x| x as usize))
+        .extend_positive(vec2(width as usize, height as usize));
+    let atlas_size = size.map(|x| x as f32);
+    let uv = pixels.as_f32().map_bounds(|pos| pos / atlas_size);
+    AtlasRegion { pixels, uv }
+}
+
+/// Packs the rects into shelves sorted by height.
+/// Returns the total size and the top-left positions of the rects in the same order.
+pub(crate) fn pack(sizes: &[vec2<u32>], padding: u32) -> (vec2<u32>, Vec<vec2<u32>>) {
+    let total_area: u64 = sizes.iter().map(|size| size.x as u64 * size.y as u64).sum();
+    let max_width = sizes.iter().map(|size| size.x).max().unwrap_or(0);
+    let width = ((total_area as f64).sqrt().ceil() as u32)
+        .next_power_of_two()
+        .max(max_width + padding);
+
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sizes[i].y));
+
+    let mut positions = vec![vec2::ZERO; sizes.len()];
+    let mut pos = vec2::splat(padding);
+    let mut shelf_height = 0;
+    for i in order {
+        let size = sizes[i];
+        if pos.x + size.x > width {
+            // Start a new shelf
+            pos = vec2(padding, pos.y + shelf_height);
+            shelf_height = 0;
+        }
+        positions[i] = pos;
+        pos.x += size.x;
+        shelf_height = shelf_height.max(size.y);
+    }
+
+    (vec2(width, pos.y + shelf_height), positions)
+}
+
+/// Check that the region and its extruded edges in the atlas image are filled with the color.
+#[cfg(test)]
+pub(crate) fn assert_extruded(
+    image: &image::RgbaImage,
+    region: &AtlasRegion,
+    extrude: usize,
+    color: [u8; 4],
+) {
+    // Flip back into image coordinates
+    let height = image.height() as usize;
+    let pixels = region.pixels.extend_uniform(extrude);
+    for x in pixels.min.x..pixels.max.x {
+        for y in pixels.min.y..pixels.max.y {
+            let pixel = image.get_pixel(x as u32, (height - 1 - y) as u32);
+            assert_eq!(pixel.0, color, "pixel ({x}, {y}) of {region:?}");
+        }
+    }
+}
+
+#[test]
+fn test_atlas() {
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]];
+    let sizes = [(8, 4), (3, 10), (5, 5)];
+
+    let mut builder = AtlasBuilder::new().with_padding(1).with_extrude(1);
+    for (i, (&color, (width, height))) in colors.iter().zip(sizes).enumerate() {
+        let image = image::RgbaImage::from_pixel(width, height, image::Rgba(color));
+        builder.add(format!("{i}"), image);
+    }
+    let atlas = builder.build();
+    let size = atlas.size();
+
+    let regions: Vec<AtlasRegion> = (0..3).map(|i| atlas.get(&i.to_string()).unwrap()).collect();
+    for (i, region) in regions.iter().enumerate() {
+        assert_eq!(
+            region.pixels.size(),
+            vec2(sizes[i].0 as usize, sizes[i].1 as usize)
+        );
+        assert!(region.pixels.max.x <= size.x && region.pixels.max.y <= size.y);
+        assert!(region.uv.min.x >= 0.0 && region.uv.max.y <= 1.0);
+
+        assert_extruded(&atlas.image, region, 1, colors[i]);
+
+        // Regions and their extruded borders do not overlap
+        for other in &regions[i + 1..] {
+            let a = region.pixels.extend_uniform(1);
+            let b = other.pixels.extend_uniform(1);
+            let overlap =
+                a.min.x < b.max.x && b.min.x < a.max.x && a.min.y < b.max.y && b.min.y < a.max.y;
+            assert!(!overlap, "{a:?} and {b:?} overlap");
+        }
+    }
+}
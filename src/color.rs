@@ -0,0 +1,374 @@
+use geng::prelude::*;
+
+/// A color in the HSV (hue, saturation, value) color space.
+/// All components are in range `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+    pub a: f32,
+}
+
+/// A color in the HSL (hue, saturation, lightness) color space.
+/// All components are in range `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+    pub a: f32,
+}
+
+/// A color in the [OKLab](https://bottosson.github.io/posts/oklab/) color space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    /// Perceived lightness.
+    pub l: f32,
+    /// Green-red axis.
+    pub a: f32,
+    /// Blue-yellow axis.
+    pub b: f32,
+    pub alpha: f32,
+}
+
+/// Parse a color from a hex string.
+/// Accepts `rgb`, `rgba`, `rrggbb`, and `rrggbbaa` formats with an optional `#` prefix.
+pub fn parse_hex(s: &str) -> anyhow::Result<Rgba<f32>> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+    if !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        anyhow::bail!("invalid hex color {s:?}");
+    }
+
+    let channel = |i: usize, len: usize| -> anyhow::Result<u8> {
+        let digits = &hex[i * len..(i + 1) * len];
+        let value = u8::from_str_radix(digits, 16)?;
+        // Expand short form: `f` -> `ff`
+        Ok(if len == 1 { value * 0x11 } else { value })
+    };
+    let (len, alpha) = match hex.len() {
+        3 => (1, false),
+        4 => (1, true),
+        6 => (2, false),
+        8 => (2, true),
+        _ => anyhow::bail!("invalid hex color length {s:?}"),
+    };
+
+    let color = Rgba {
+        r: channel(0, len)?,
+        g: channel(1, len)?,
+        b: channel(2, len)?,
+        a: if alpha { channel(3, len)? } else { 0xff },
+    };
+    Ok(rgba_to_f32(color))
+}
+
+/// Format the color as a `#rrggbb` hex string, ignoring alpha.
+pub fn to_hex(color: Rgba<f32>) -> String {
+    let color = rgba_to_u8(color);
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Format the color as a `#rrggbbaa` hex string.
+pub fn to_hex_alpha(color: Rgba<f32>) -> String {
+    let color = rgba_to_u8(color);
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        color.r, color.g, color.b, color.a
+    )
+}
+
+/// Convert the color with components in range `0..=255` into range `0.0..=1.0`.
+/// No gamma correction is applied.
+pub fn rgba_to_f32(color: Rgba<u8>) -> Rgba<f32> {
+    let f = |x: u8| x as f32 / 255.0;
+    Rgba {
+        r: f(color.r),
+        g: f(color.g),
+        b: f(color.b),
+        a: f(color.a),
+    }
+}
+
+/// Convert the color with components in range `0.0..=1.0` into range `0..=255`.
+/// Components are clamped. No gamma correction is applied.
+pub fn rgba_to_u8(color: Rgba<f32>) -> Rgba<u8> {
+    let f = |x: f32| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Rgba {
+        r: f(color.r),
+        g: f(color.g),
+        b: f(color.b),
+        a: f(color.a),
+    }
+}
+
+/// Convert an sRGB encoded component into linear space.
+pub fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert a linear component into sRGB encoding.
+pub fn linear_to_srgb(x: f32) -> f32 {
+    if x <= 0.003_130_8 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Convert an sRGB encoded color into a linear color. Alpha is left unchanged.
+pub fn srgb_u8_to_linear(color: Rgba<u8>) -> Rgba<f32> {
+    let color = rgba_to_f32(color);
+    Rgba {
+        r: srgb_to_linear(color.r),
+        g: srgb_to_linear(color.g),
+        b: srgb_to_linear(color.b),
+        a: color.a,
+    }
+}
+
+/// Convert a linear color into an sRGB encoded color. Alpha is left unchanged.
+pub fn linear_to_srgb_u8(color: Rgba<f32>) -> Rgba<u8> {
+    rgba_to_u8(Rgba {
+        r: linear_to_srgb(color.r),
+        g: linear_to_srgb(color.g),
+        b: linear_to_srgb(color.b),
+        a: color.a,
+    })
+}
+
+/// Calculate hue in range `0.0..1.0`.
+fn hue(color: Rgba<f32>, max: f32, delta: f32) -> f32 {
+    let Rgba { r, g, b, .. } = color;
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    h / 6.0
+}
+
+/// Construct rgb from hue, chroma, and the value to add to each component.
+fn from_hue(h: f32, c: f32, m: f32, a: f32) -> Rgba<f32> {
+    let h = h.rem_euclid(1.0) * 6.0;
+    let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    Rgba::new(r + m, g + m, b + m, a)
+}
+
+impl From<Rgba<f32>> for Hsv {
+    fn from(color: Rgba<f32>) -> Self {
+        let max = color.r.max(color.g).max(color.b);
+        let min = color.r.min(color.g).min(color.b);
+        let delta = max - min;
+        Self {
+            h: hue(color, max, delta),
+            s: if max == 0.0 { 0.0 } else { delta / max },
+            v: max,
+            a: color.a,
+        }
+    }
+}
+
+impl From<Hsv> for Rgba<f32> {
+    fn from(color: Hsv) -> Self {
+        let c = color.v * color.s;
+        from_hue(color.h, c, color.v - c, color.a)
+    }
+}
+
+impl From<Rgba<f32>> for Hsl {
+    fn from(color: Rgba<f32>) -> Self {
+        let max = color.r.max(color.g).max(color.b);
+        let min = color.r.min(color.g).min(color.b);
+        let delta = max - min;
+        let l = (max + min) / 2.0;
+        Self {
+            h: hue(color, max, delta),
+            s: if delta == 0.0 {
+                0.0
+            } else {
+                delta / (1.0 - (2.0 * l - 1.0).abs())
+            },
+            l,
+            a: color.a,
+        }
+    }
+}
+
+impl From<Hsl> for Rgba<f32> {
+    fn from(color: Hsl) -> Self {
+        let c = (1.0 - (2.0 * color.l - 1.0).abs()) * color.s;
+        from_hue(color.h, c, color.l - c / 2.0, color.a)
+    }
+}
+
+impl From<Rgba<f32>> for Oklab {
+    /// Converts an sRGB encoded color into OKLab.
+    fn from(color: Rgba<f32>) -> Self {
+        let r = srgb_to_linear(color.r) as f64;
+        let g = srgb_to_linear(color.g) as f64;
+        let b = srgb_to_linear(color.b) as f64;
+
+        let l = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+        let m = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+        let s = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+
+        Self {
+            l: (0.210_454_255_3 * l + 0.793_617_785_0 * m - 0.004_072_046_8 * s) as f32,
+            a: (1.977_998_495_1 * l - 2.428_592_205_0 * m + 0.450_593_709_9 * s) as f32,
+            b: (0.025_904_037_1 * l + 0.782_771_766_2 * m - 0.808_675_766_0 * s) as f32,
+            alpha: color.a,
+        }
+    }
+}
+
+impl From<Oklab> for Rgba<f32> {
+    /// Converts an OKLab color into sRGB encoding.
+    fn from(color: Oklab) -> Self {
+        let (l, a, b) = (color.l as f64, color.a as f64, color.b as f64);
+
+        let l_ = (l + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+        let m_ = (l - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+        let s_ = (l - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+
+        let r = 4.076_741_662_1 * l_ - 3.307_711_591_3 * m_ + 0.230_969_929_2 * s_;
+        let g = -1.268_438_004_6 * l_ + 2.609_757_401_1 * m_ - 0.341_319_396_5 * s_;
+        let b = -0.004_196_086_3 * l_ - 0.703_418_614_7 * m_ + 1.707_614_701_0 * s_;
+
+        Rgba::new(
+            linear_to_srgb(r as f32),
+            linear_to_srgb(g as f32),
+            linear_to_srgb(b as f32),
+            color.alpha,
+        )
+    }
+}
+
+/// Serde helpers for (de)serializing colors as hex strings.
+///
+/// Use with `#[serde(with = "geng_utils::color::hex")]`.
+/// Serializes as `#rrggbb` for opaque colors and `#rrggbbaa` otherwise.
+pub mod hex {
+    use super::*;
+
+    pub fn serialize<S: serde::Serializer>(
+        color: &Rgba<f32>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let hex = if rgba_to_u8(*color).a == 0xff {
+            to_hex(*color)
+        } else {
+            to_hex_alpha(*color)
+        };
+        serializer.serialize_str(&hex)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Rgba<f32>, D::Error> {
+        let hex = <String as serde::Deserialize>::deserialize(deserializer)?;
+        parse_hex(&hex).map_err(serde::de::Error::custom)
+    }
+}
+
+#[test]
+fn test_color_conversions() {
+    fn assert_approx(a: Rgba<f32>, b: Rgba<f32>) {
+        let eq = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert!(
+            eq(a.r, b.r) && eq(a.g, b.g) && eq(a.b, b.b) && eq(a.a, b.a),
+            "{a:?} != {b:?}"
+        );
+    }
+
+    let color = parse_hex("#ff8800").unwrap();
+    assert_eq!(
+        rgba_to_u8(color),
+        Rgba {
+            r: 0xff,
+            g: 0x88,
+            b: 0x00,
+            a: 0xff
+        }
+    );
+    assert_eq!(to_hex(color), "#ff8800");
+    assert_eq!(to_hex_alpha(parse_hex("f80c").unwrap()), "#ff8800cc");
+    assert!(parse_hex("#ff880").is_err());
+    assert!(parse_hex("#gg8800").is_err());
+
+    let colors = [
+        Rgba::new(1.0, 0.5, 0.0, 1.0),
+        Rgba::new(0.2, 0.4, 0.9, 0.5),
+        Rgba::new(0.3, 0.3, 0.3, 1.0),
+        Rgba::new(0.0, 0.0, 0.0, 0.0),
+    ];
+    for color in colors {
+        assert_approx(Rgba::from(Hsv::from(color)), color);
+        assert_approx(Rgba::from(Hsl::from(color)), color);
+        assert_approx(Rgba::from(Oklab::from(color)), color);
+    }
+    let hsv = Hsv::from(Rgba::new(1.0, 0.0, 0.0, 1.0));
+    assert_eq!(
+        hsv,
+        Hsv {
+            h: 0.0,
+            s: 1.0,
+            v: 1.0,
+            a: 1.0
+        }
+    );
+    assert!((Oklab::from(Rgba::<f32>::WHITE).l - 1.0).abs() < 1e-4);
+
+    for x in [0, 1, 10, 128, 200, 255] {
+        let color = Rgba {
+            r: x,
+            g: x,
+            b: x,
+            a: x,
+        };
+        assert_eq!(linear_to_srgb_u8(srgb_u8_to_linear(color)), color);
+    }
+}
+
+#[test]
+fn test_hex_serde() {
+    #[derive(Debug, serde::Serialize, serde::Deserialize)]
+    struct Config {
+        #[serde(with = "hex")]
+        color: Rgba<f32>,
+    }
+
+    let opaque: Config = serde_json::from_str(r##"{"color":"#ff8800"}"##).unwrap();
+    assert_eq!(rgba_to_u8(opaque.color), Rgba::new(0xff, 0x88, 0x00, 0xff));
+    assert_eq!(
+        serde_json::to_string(&opaque).unwrap(),
+        r##"{"color":"#ff8800"}"##
+    );
+
+    let translucent: Config = serde_json::from_str(r##"{"color":"#ff880080"}"##).unwrap();
+    assert_eq!(rgba_to_u8(translucent.color).a, 0x80);
+    assert_eq!(
+        serde_json::to_string(&translucent).unwrap(),
+        r##"{"color":"#ff880080"}"##
+    );
+
+    assert!(serde_json::from_str::<Config>(r##"{"color":"#ff88000"}"##).is_err());
+    assert!(serde_json::from_str::<Config>(r##"{"color":"#ff88z0"}"##).is_err());
+    assert!(serde_json::from_str::<Config>(r#"{"color":42}"#).is_err());
+}
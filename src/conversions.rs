@@ -1,4 +1,4 @@
-use geng::prelude::{Aabb2, Angle, Float, R32, R64, mat3, mat4, r32, vec2, vec3, vec4};
+use geng::prelude::{Aabb2, Angle, Float, R32, R64, mat3, mat4, r32, r64, vec2, vec3, vec4};
 
 pub trait ResultUnwrapEitherExt {
     type Value;
@@ -14,141 +14,439 @@ impl<T> ResultUnwrapEitherExt for Result<T, T> {
     }
 }
 
-/// A trait for converting into an [R32].
+/// A trait for converting into an [R32], [f64], or [R64].
 pub trait RealConversions {
     fn as_r32(&self) -> R32;
+    fn as_f64(&self) -> f64;
+    fn as_r64(&self) -> R64;
 }
 
-impl<T: Float> RealConversions for T {
+impl<T: Float + RealElement> RealConversions for T {
     fn as_r32(&self) -> R32 {
-        r32(self.as_f32())
+        CastElement::<R32>::cast_element(*self)
+    }
+    fn as_f64(&self) -> f64 {
+        CastElement::<f64>::cast_element(*self)
+    }
+    fn as_r64(&self) -> R64 {
+        CastElement::<R64>::cast_element(*self)
     }
 }
 
-/// A trait for converting [Angle]'s inner types between [f32] and [R32].
+/// A trait for converting [Angle]'s inner types between [f32], [f64], [R32], and [R64].
 pub trait AngleRealConversions {
     fn as_f32(&self) -> Angle<f32>;
     fn as_r32(&self) -> Angle<R32>;
+    fn as_f64(&self) -> Angle<f64>;
+    fn as_r64(&self) -> Angle<R64>;
 }
 
-/// A trait for converting [vec2]'s inner types between [f32] and [R32].
+/// A trait for converting [vec2]'s inner types between [f32], [f64], [R32], and [R64].
 pub trait Vec2RealConversions {
     fn as_f32(&self) -> vec2<f32>;
     fn as_r32(&self) -> vec2<R32>;
+    fn as_f64(&self) -> vec2<f64>;
+    fn as_r64(&self) -> vec2<R64>;
 }
 
-/// A trait for converting [vec3]'s inner types between [f32] and [R32].
+/// A trait for converting [vec3]'s inner types between [f32], [f64], [R32], and [R64].
 pub trait Vec3RealConversions {
     fn as_f32(&self) -> vec3<f32>;
     fn as_r32(&self) -> vec3<R32>;
+    fn as_f64(&self) -> vec3<f64>;
+    fn as_r64(&self) -> vec3<R64>;
 }
 
-/// A trait for converting [vec4]'s inner types between [f32] and [R32].
+/// A trait for converting [vec4]'s inner types between [f32], [f64], [R32], and [R64].
 pub trait Vec4RealConversions {
     fn as_f32(&self) -> vec4<f32>;
     fn as_r32(&self) -> vec4<R32>;
+    fn as_f64(&self) -> vec4<f64>;
+    fn as_r64(&self) -> vec4<R64>;
 }
 
-/// A trait for converting [Aabb2]'s inner types between [f32] and [R32].
+/// A trait for converting [Aabb2]'s inner types between [f32], [f64], [R32], and [R64].
 pub trait Aabb2RealConversions {
     fn as_f32(&self) -> Aabb2<f32>;
     fn as_r32(&self) -> Aabb2<R32>;
+    fn as_f64(&self) -> Aabb2<f64>;
+    fn as_r64(&self) -> Aabb2<R64>;
 }
 
-/// A trait for converting [mat3]'s inner types between [f32] and [R32].
+/// A trait for converting [mat3]'s inner types between [f32], [f64], [R32], and [R64].
 pub trait Mat3RealConversions {
     fn as_f32(&self) -> mat3<f32>;
     fn as_r32(&self) -> mat3<R32>;
+    fn as_f64(&self) -> mat3<f64>;
+    fn as_r64(&self) -> mat3<R64>;
 }
 
-/// A trait for converting [mat4]'s inner types between [f32] and [R32].
+/// A trait for converting [mat4]'s inner types between [f32], [f64], [R32], and [R64].
 pub trait Mat4RealConversions {
     fn as_f32(&self) -> mat4<f32>;
     fn as_r32(&self) -> mat4<R32>;
+    fn as_f64(&self) -> mat4<f64>;
+    fn as_r64(&self) -> mat4<R64>;
 }
 
-macro_rules! impl_lossy {
-    ($trait:ident, $typ:tt, $t:ident) => {
-        impl $trait for $typ<$t> {
-            fn as_f32(&self) -> $typ<f32> {
-                self.map(|x| x as f32)
+/// Conversion of a single numeric element into `T`.
+pub trait CastElement<T>: Copy {
+    fn cast_element(self) -> T;
+}
+
+/// Element types that can be cast into any of [f32], [f64], [R32], and [R64].
+pub trait RealElement:
+    CastElement<f32> + CastElement<f64> + CastElement<R32> + CastElement<R64>
+{
+}
+
+impl<T> RealElement for T where
+    T: CastElement<f32> + CastElement<f64> + CastElement<R32> + CastElement<R64>
+{
+}
+
+/// A container of [Self::Element]'s that can map them into another type.
+///
+/// Implementing this trait for a custom type (e.g. a circle or a segment)
+/// makes it [Cast]able without any extra conversion traits.
+pub trait MapElements {
+    type Element;
+    type Output<U>;
+    fn map_elements<U>(&self, f: impl Fn(Self::Element) -> U) -> Self::Output<U>;
+}
+
+/// A generic numeric cast for any container, e.g. `vec2(1, 2).cast::<f32>()`.
+pub trait Cast: MapElements {
+    fn cast<U>(&self) -> Self::Output<U>
+    where
+        Self::Element: CastElement<U>,
+    {
+        self.map_elements(CastElement::<U>::cast_element)
+    }
+}
+
+impl<C: MapElements> Cast for C {}
+
+macro_rules! impl_cast_lossy {
+    ($($t:ident),*) => {
+        $(
+            impl CastElement<f32> for $t {
+                fn cast_element(self) -> f32 {
+                    self as f32
+                }
             }
-            fn as_r32(&self) -> $typ<R32> {
-                self.map(|x| r32(x as f32))
+            impl CastElement<f64> for $t {
+                fn cast_element(self) -> f64 {
+                    self as f64
+                }
             }
-        }
+            impl CastElement<R32> for $t {
+                fn cast_element(self) -> R32 {
+                    r32(self as f32)
+                }
+            }
+            impl CastElement<R64> for $t {
+                fn cast_element(self) -> R64 {
+                    r64(self as f64)
+                }
+            }
+        )*
     };
 }
 
-macro_rules! impl_float {
-    ($trait:ident, $typ:tt, $t:ident) => {
-        impl $trait for $typ<$t> {
-            fn as_f32(&self) -> $typ<f32> {
-                self.map(|x| x.as_f32())
+macro_rules! impl_cast_float {
+    ($t:ident, $x:ident => $f32:expr, $f64:expr) => {
+        impl CastElement<f32> for $t {
+            fn cast_element(self) -> f32 {
+                let $x = self;
+                $f32
             }
-            fn as_r32(&self) -> $typ<R32> {
-                self.map(|x| r32(x.as_f32()))
+        }
+        impl CastElement<f64> for $t {
+            fn cast_element(self) -> f64 {
+                let $x = self;
+                $f64
+            }
+        }
+        impl CastElement<R32> for $t {
+            fn cast_element(self) -> R32 {
+                r32(CastElement::<f32>::cast_element(self))
+            }
+        }
+        impl CastElement<R64> for $t {
+            fn cast_element(self) -> R64 {
+                r64(CastElement::<f64>::cast_element(self))
             }
         }
     };
 }
 
-macro_rules! impl_all_lossy {
-    ($trait:ident, $typ:tt) => {
-        impl_lossy!($trait, $typ, usize);
-        impl_lossy!($trait, $typ, u8);
-        impl_lossy!($trait, $typ, u16);
-        impl_lossy!($trait, $typ, u32);
-        impl_lossy!($trait, $typ, u64);
-        // impl_lossy!($trait, $typ, u128); // TODO: Aabb2 does not fully support u128
-        impl_lossy!($trait, $typ, isize);
-        impl_lossy!($trait, $typ, i8);
-        impl_lossy!($trait, $typ, i16);
-        impl_lossy!($trait, $typ, i32);
-        impl_lossy!($trait, $typ, i64);
-        // impl_lossy!($trait, $typ, i128);
+impl_cast_lossy!(
+    usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128
+);
+impl_cast_float!(f32, x => x, x as f64);
+impl_cast_float!(f64, x => x as f32, x);
+impl_cast_float!(R32, x => x.raw(), x.raw() as f64);
+impl_cast_float!(R64, x => x.raw() as f32, x.raw());
+
+macro_rules! impl_map_scalar {
+    ($($t:ident),*) => {
+        $(
+            impl MapElements for $t {
+                type Element = $t;
+                type Output<U> = U;
+                fn map_elements<U>(&self, f: impl Fn($t) -> U) -> U {
+                    f(*self)
+                }
+            }
+        )*
     };
 }
 
-macro_rules! impl_all_float {
-    ($trait:ident, $typ:tt) => {
-        impl_float!($trait, $typ, f32);
-        impl_float!($trait, $typ, f64);
-        impl_float!($trait, $typ, R32);
-        impl_float!($trait, $typ, R64);
+impl_map_scalar!(
+    usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128, f32, f64, R32, R64
+);
+
+macro_rules! impl_map {
+    ($typ:ident) => {
+        impl<T: Copy> MapElements for $typ<T> {
+            type Element = T;
+            type Output<U> = $typ<U>;
+            fn map_elements<U>(&self, f: impl Fn(T) -> U) -> $typ<U> {
+                self.map(f)
+            }
+        }
     };
 }
 
-impl_all_float!(AngleRealConversions, Angle);
+impl_map!(vec2);
+impl_map!(vec3);
+impl_map!(vec4);
+impl_map!(mat3);
+impl_map!(mat4);
+
+// Mapping the corners directly, since `Aabb2::map` does not support 128-bit integers.
+impl<T: Copy> MapElements for Aabb2<T> {
+    type Element = T;
+    type Output<U> = Aabb2<U>;
+    fn map_elements<U>(&self, f: impl Fn(T) -> U) -> Aabb2<U> {
+        Aabb2 {
+            min: self.min.map(&f),
+            max: self.max.map(&f),
+        }
+    }
+}
 
-impl_all_lossy!(Vec2RealConversions, vec2);
-impl_all_float!(Vec2RealConversions, vec2);
-impl_all_lossy!(Vec3RealConversions, vec3);
-impl_all_float!(Vec3RealConversions, vec3);
-impl_all_lossy!(Vec4RealConversions, vec4);
-impl_all_float!(Vec4RealConversions, vec4);
+macro_rules! impl_real_conversions {
+    ($trait:ident, $typ:ident) => {
+        impl<T: RealElement> $trait for $typ<T> {
+            fn as_f32(&self) -> $typ<f32> {
+                self.cast::<f32>()
+            }
+            fn as_r32(&self) -> $typ<R32> {
+                self.cast::<R32>()
+            }
+            fn as_f64(&self) -> $typ<f64> {
+                self.cast::<f64>()
+            }
+            fn as_r64(&self) -> $typ<R64> {
+                self.cast::<R64>()
+            }
+        }
+    };
+}
 
-impl_all_lossy!(Aabb2RealConversions, Aabb2);
-impl_all_float!(Aabb2RealConversions, Aabb2);
+impl_real_conversions!(Vec2RealConversions, vec2);
+impl_real_conversions!(Vec3RealConversions, vec3);
+impl_real_conversions!(Vec4RealConversions, vec4);
+impl_real_conversions!(Aabb2RealConversions, Aabb2);
+impl_real_conversions!(Mat3RealConversions, mat3);
+impl_real_conversions!(Mat4RealConversions, mat4);
 
-impl<T: Float> Mat3RealConversions for mat3<T> {
-    fn as_f32(&self) -> mat3<f32> {
-        self.map(|x| x.as_f32())
+impl<T: Float + RealElement> AngleRealConversions for Angle<T> {
+    fn as_f32(&self) -> Angle<f32> {
+        Angle::from_radians(CastElement::<f32>::cast_element(self.as_radians()))
     }
-    fn as_r32(&self) -> mat3<R32> {
-        self.map(|x| r32(x.as_f32()))
+    fn as_r32(&self) -> Angle<R32> {
+        Angle::from_radians(CastElement::<R32>::cast_element(self.as_radians()))
+    }
+    fn as_f64(&self) -> Angle<f64> {
+        Angle::from_radians(CastElement::<f64>::cast_element(self.as_radians()))
+    }
+    fn as_r64(&self) -> Angle<R64> {
+        Angle::from_radians(CastElement::<R64>::cast_element(self.as_radians()))
+    }
+}
+
+/// A trait for converting an [Angle] into a direction.
+pub trait AngleDirectionConversions<T> {
+    /// Returns a unit vector pointing in the direction of the angle.
+    fn to_direction(&self) -> vec2<T>;
+}
+
+impl<T: Float> AngleDirectionConversions<T> for Angle<T> {
+    fn to_direction(&self) -> vec2<T> {
+        let radians = self.as_radians();
+        vec2(radians.cos(), radians.sin())
     }
 }
 
-impl<T: Float> Mat4RealConversions for mat4<T> {
-    fn as_f32(&self) -> mat4<f32> {
-        self.map(|x| x.as_f32())
+/// A trait for converting [vec2] to and from polar coordinates.
+pub trait Vec2PolarConversions<T> {
+    /// Returns the length and the direction of the vector.
+    fn to_polar(&self) -> (T, Angle<T>);
+    /// Constructs a vector from its length and direction.
+    fn from_polar(radius: T, angle: Angle<T>) -> Self;
+    /// Returns the signed shortest angle to rotate this direction into the `other` one,
+    /// in range `-PI..=PI`. Positive angles are counter-clockwise.
+    fn signed_angle_to(&self, other: Self) -> Angle<T>;
+}
+
+impl<T: Float> Vec2PolarConversions<T> for vec2<T> {
+    fn to_polar(&self) -> (T, Angle<T>) {
+        let angle = Angle::from_radians(T::atan2(self.y, self.x));
+        (self.len(), angle)
+    }
+
+    fn from_polar(radius: T, angle: Angle<T>) -> Self {
+        angle.to_direction() * radius
+    }
+
+    fn signed_angle_to(&self, other: Self) -> Angle<T> {
+        let cross = self.x * other.y - self.y * other.x;
+        let dot = self.x * other.x + self.y * other.y;
+        Angle::from_radians(T::atan2(cross, dot))
     }
-    fn as_r32(&self) -> mat4<R32> {
-        self.map(|x| r32(x.as_f32()))
+}
+
+/// Rounding mode used when converting floats into integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Round towards negative infinity.
+    Floor,
+    /// Round towards positive infinity.
+    Ceil,
+    /// Round to the nearest integer, half-way cases away from zero.
+    Round,
+    /// Round towards zero.
+    Trunc,
+}
+
+impl Rounding {
+    /// Round the value according to the mode.
+    pub fn apply(self, x: f64) -> f64 {
+        match self {
+            Self::Floor => x.floor(),
+            Self::Ceil => x.ceil(),
+            Self::Round => x.round(),
+            Self::Trunc => x.trunc(),
+        }
     }
 }
 
+/// Rounding mode used when converting an [Aabb2] into integers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AabbRounding {
+    /// Round both `min` and `max` with the same mode.
+    Each(Rounding),
+    /// Expand to the nearest integers, so that the result contains the original box.
+    Outward,
+    /// Shrink to the nearest integers, so that the result is contained in the original box.
+    /// Boxes smaller than a single unit may end up with `min > max`.
+    Inward,
+}
+
+/// Integer types that can be constructed from a float, saturating at the bounds of the type.
+/// NaN is converted to zero.
+pub trait SaturatingFromFloat: Copy {
+    fn saturating_from_f64(x: f64) -> Self;
+}
+
+macro_rules! impl_saturating {
+    ($($t:ident),*) => {
+        $(
+            impl SaturatingFromFloat for $t {
+                fn saturating_from_f64(x: f64) -> Self {
+                    // `as` casts from floats to integers are saturating
+                    x as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_saturating!(
+    usize, u8, u16, u32, u64, u128, isize, i8, i16, i32, i64, i128
+);
+
+/// A trait for converting [vec2]'s float types into integers with explicit rounding.
+pub trait Vec2IntConversions {
+    fn to_int<I: SaturatingFromFloat>(&self, rounding: Rounding) -> vec2<I>;
+}
+
+/// A trait for converting [vec3]'s float types into integers with explicit rounding.
+pub trait Vec3IntConversions {
+    fn to_int<I: SaturatingFromFloat>(&self, rounding: Rounding) -> vec3<I>;
+}
+
+/// A trait for converting [vec4]'s float types into integers with explicit rounding.
+pub trait Vec4IntConversions {
+    fn to_int<I: SaturatingFromFloat>(&self, rounding: Rounding) -> vec4<I>;
+}
+
+/// A trait for converting [Aabb2]'s float types into integers with explicit rounding.
+pub trait Aabb2IntConversions {
+    fn to_int<I: SaturatingFromFloat>(&self, rounding: AabbRounding) -> Aabb2<I>;
+}
+
+macro_rules! impl_int {
+    ($trait:ident, $typ:tt, $t:ident) => {
+        impl $trait for $typ<$t> {
+            fn to_int<I: SaturatingFromFloat>(&self, rounding: Rounding) -> $typ<I> {
+                self.map(|x| {
+                    I::saturating_from_f64(rounding.apply(CastElement::<f64>::cast_element(x)))
+                })
+            }
+        }
+    };
+}
+
+macro_rules! impl_all_int {
+    ($trait:ident, $typ:tt) => {
+        impl_int!($trait, $typ, f32);
+        impl_int!($trait, $typ, f64);
+        impl_int!($trait, $typ, R32);
+        impl_int!($trait, $typ, R64);
+    };
+}
+
+impl_all_int!(Vec2IntConversions, vec2);
+impl_all_int!(Vec3IntConversions, vec3);
+impl_all_int!(Vec4IntConversions, vec4);
+
+macro_rules! impl_aabb2_int {
+    ($t:ident) => {
+        impl Aabb2IntConversions for Aabb2<$t> {
+            fn to_int<I: SaturatingFromFloat>(&self, rounding: AabbRounding) -> Aabb2<I> {
+                let (min, max) = match rounding {
+                    AabbRounding::Each(rounding) => (rounding, rounding),
+                    AabbRounding::Outward => (Rounding::Floor, Rounding::Ceil),
+                    AabbRounding::Inward => (Rounding::Ceil, Rounding::Floor),
+                };
+                Aabb2 {
+                    min: self.min.to_int(min),
+                    max: self.max.to_int(max),
+                }
+            }
+        }
+    };
+}
+
+impl_aabb2_int!(f32);
+impl_aabb2_int!(f64);
+impl_aabb2_int!(R32);
+impl_aabb2_int!(R64);
+
 #[test]
 fn test_unwrap_either() {
     let result: Result<i32, i32> = Ok(1);
@@ -156,3 +454,147 @@ fn test_unwrap_either() {
     let result: Result<i32, i32> = Err(2);
     assert_eq!(result.unwrap_either(), 2);
 }
+
+#[test]
+fn test_int_conversions() {
+    let v = vec2(-1.5_f32, 2.5);
+    assert_eq!(v.to_int::<i32>(Rounding::Floor), vec2(-2, 2));
+    assert_eq!(v.to_int::<i32>(Rounding::Ceil), vec2(-1, 3));
+    assert_eq!(v.to_int::<i32>(Rounding::Round), vec2(-2, 3));
+    assert_eq!(v.to_int::<i32>(Rounding::Trunc), vec2(-1, 2));
+
+    // Saturating
+    assert_eq!(v.to_int::<u8>(Rounding::Floor), vec2(0, 2));
+    let v = vec3(1e20_f32, -1e20, f32::NAN);
+    assert_eq!(
+        v.to_int::<i32>(Rounding::Round),
+        vec3(i32::MAX, i32::MIN, 0)
+    );
+
+    let aabb = Aabb2 {
+        min: vec2(0.5_f32, -0.5),
+        max: vec2(2.5, 1.5),
+    };
+    assert_eq!(
+        aabb.to_int::<i32>(AabbRounding::Outward),
+        Aabb2 {
+            min: vec2(0, -1),
+            max: vec2(3, 2),
+        }
+    );
+    assert_eq!(
+        aabb.to_int::<i32>(AabbRounding::Inward),
+        Aabb2 {
+            min: vec2(1, 0),
+            max: vec2(2, 1),
+        }
+    );
+}
+
+#[test]
+fn test_f64_conversions() {
+    // 2^24 + 1 is not representable in f32
+    let x = 16_777_217_i64;
+    assert_eq!(vec2(x, -x).as_f64(), vec2(x as f64, -x as f64));
+    assert_ne!(vec2(x, -x).as_f32().as_f64(), vec2(x as f64, -x as f64));
+
+    let x = 0.1_f64;
+    assert_eq!(r64(x).as_f64(), x);
+    assert_eq!(vec3(x, x, x).as_r64(), vec3(r64(x), r64(x), r64(x)));
+    assert_eq!(mat3::<R64>::identity().as_f64(), mat3::<f64>::identity());
+    assert_eq!(0.5_f32.as_r64(), r64(0.5));
+}
+
+#[test]
+fn test_128_conversions() {
+    let v = vec2(u128::MAX, (1 << 100) | 1);
+    // Precision is lost for large values
+    assert_eq!(v.as_f64(), vec2(2.0_f64.powi(128), 2.0_f64.powi(100)));
+    // `u128::MAX` does not fit into an f32 and overflows to infinity
+    assert_eq!(v.as_f32().x, f32::INFINITY);
+
+    let aabb = Aabb2 {
+        min: vec2(i128::MIN, -1),
+        max: vec2(i128::MAX, 1),
+    };
+    assert_eq!(
+        aabb.as_f32(),
+        Aabb2 {
+            min: vec2(-2.0_f32.powi(127), -1.0),
+            max: vec2(2.0_f32.powi(127), 1.0),
+        }
+    );
+    assert_eq!(
+        aabb.as_r64(),
+        Aabb2 {
+            min: vec2(r64(-2.0_f64.powi(127)), r64(-1.0)),
+            max: vec2(r64(2.0_f64.powi(127)), r64(1.0)),
+        }
+    );
+}
+
+#[test]
+fn test_polar_conversions() {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    let approx = |a: f32, b: f32| (a - b).abs() < 1e-5;
+
+    let dir = Angle::from_radians(FRAC_PI_2).to_direction();
+    assert!(approx(dir.x, 0.0) && approx(dir.y, 1.0));
+
+    let v = vec2(-3.0_f32, 4.0);
+    let (radius, angle) = v.to_polar();
+    assert!(approx(radius, 5.0));
+    let back = vec2::from_polar(radius, angle);
+    assert!(approx(back.x, v.x) && approx(back.y, v.y));
+
+    let right = vec2(1.0_f32, 0.0);
+    let up = vec2(0.0, 1.0);
+    assert!(approx(right.signed_angle_to(up).as_radians(), FRAC_PI_2));
+    assert!(approx(up.signed_angle_to(right).as_radians(), -FRAC_PI_2));
+    assert!(approx(right.signed_angle_to(-right).as_radians().abs(), PI));
+
+    let angle = vec2(r64(1.0), r64(1.0)).to_polar().1;
+    assert!(approx(angle.as_radians().raw() as f32, PI / 4.0));
+}
+
+#[test]
+fn test_cast() {
+    struct Circle<T> {
+        center: vec2<T>,
+        radius: T,
+    }
+
+    impl<T: Copy> MapElements for Circle<T> {
+        type Element = T;
+        type Output<U> = Circle<U>;
+        fn map_elements<U>(&self, f: impl Fn(T) -> U) -> Circle<U> {
+            Circle {
+                center: self.center.map(&f),
+                radius: f(self.radius),
+            }
+        }
+    }
+
+    let circle = Circle {
+        center: vec2(1_i32, 2),
+        radius: 3,
+    }
+    .cast::<f32>();
+    assert_eq!(circle.center, vec2(1.0, 2.0));
+    assert_eq!(circle.radius, 3.0);
+
+    assert_eq!(vec2(1.5_f32, 2.0).cast::<R64>(), vec2(r64(1.5), r64(2.0)));
+    assert_eq!(5_u8.cast::<f64>(), 5.0);
+    assert_eq!(
+        Aabb2 {
+            min: vec2(-1_i64, -2),
+            max: vec2(3, 4),
+        }
+        .cast::<f32>(),
+        Aabb2 {
+            min: vec2(-1.0, -2.0),
+            max: vec2(3.0, 4.0),
+        }
+    );
+}
@@ -1,58 +1,850 @@
-use geng::prelude::*;
+use geng::prelude::{future::FutureExt, *};
 
-/// A single GIF frame that has a texture and a duration.
-pub struct GifFrame {
+use crate::{atlas::AtlasRegion, texture::DrawTexture};
+
+/// A single animation frame that has a texture and a duration.
+pub struct AnimationFrame {
     pub texture: ugli::Texture,
     /// Duration of the frame in seconds.
     pub duration: f32,
 }
 
-/// GIF load options.
+/// A single decoded animation frame on the CPU, not yet uploaded into a texture.
+#[derive(Debug, Clone)]
+pub struct AnimationImageFrame {
+    pub image: image::RgbaImage,
+    /// Duration of the frame in seconds.
+    pub duration: f32,
+}
+
+/// Options for decoding animation frames and uploading them into textures.
 #[derive(Debug, Clone, Default)]
-pub struct GifOptions {
+pub struct AnimationOptions {
     pub frame: geng::asset::TextureOptions,
+    /// If a frame fails to decode, keep the frames decoded before it instead of failing.
+    /// Still fails if the first frame cannot be decoded.
+    pub allow_partial: bool,
+    /// How to store the frames in textures when loading an [Animation].
+    /// [load_gif] and [load_animation] always upload every frame into its own texture.
+    pub packing: AnimationPacking,
+}
+
+/// How the frames of an [Animation] are stored in textures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnimationPacking {
+    /// Every frame in its own texture.
+    #[default]
+    Separate,
+    /// All frames in a single texture, placed left to right in one row.
+    Strip,
+    /// All frames in a single texture, placed row by row starting from the top-left corner.
+    Grid { columns: usize },
+}
+
+/// A single frame in an [AnimationAtlas].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimationAtlasFrame {
+    pub region: AtlasRegion,
+    /// Duration of the frame in seconds.
+    pub duration: f32,
+}
+
+/// Animation frames packed into a single texture.
+pub struct AnimationAtlas {
+    pub texture: ugli::Texture,
+    pub frames: Vec<AnimationAtlasFrame>,
 }
 
+// Names from when only GIF was supported, kept for compatibility
+pub type GifFrame = AnimationFrame;
+pub type GifOptions = AnimationOptions;
+
 /// Load GIF frame from the given file path.
+/// Every frame gets its own texture regardless of the [packing](AnimationOptions::packing),
+/// load an [Animation] to pack the frames.
 pub async fn load_gif(
     manager: &geng::asset::Manager,
     path: impl AsRef<std::path::Path>,
-    options: GifOptions,
-) -> anyhow::Result<Vec<GifFrame>> {
-    use image::AnimationDecoder;
+    options: AnimationOptions,
+) -> anyhow::Result<Vec<AnimationFrame>> {
+    load_frames(manager, path.as_ref(), &options, decode_gif).await
+}
+
+/// Load an animated image (GIF, APNG, or WebP) from the given file path.
+/// The format is detected from the contents, see [decode_animation].
+/// Every frame gets its own texture regardless of the [packing](AnimationOptions::packing),
+/// load an [Animation] to pack the frames.
+pub async fn load_animation(
+    manager: &geng::asset::Manager,
+    path: impl AsRef<std::path::Path>,
+    options: AnimationOptions,
+) -> anyhow::Result<Vec<AnimationFrame>> {
+    load_frames(manager, path.as_ref(), &options, decode_animation).await
+}
+
+/// Decodes the frames of an animated image from its bytes.
+type Decode = fn(&[u8], &AnimationOptions) -> anyhow::Result<Vec<AnimationImageFrame>>;
 
-    let path = path.as_ref();
-    log::debug!("Loading gif at {:?}", path);
+async fn load_frames(
+    manager: &geng::asset::Manager,
+    path: &std::path::Path,
+    options: &AnimationOptions,
+    decode: Decode,
+) -> anyhow::Result<Vec<AnimationFrame>> {
+    let frames = load_images(manager, path, options, decode).await?;
+    Ok(upload_animation(manager.ugli(), frames, options))
+}
 
+async fn load_images(
+    manager: &geng::asset::Manager,
+    path: &std::path::Path,
+    options: &AnimationOptions,
+    decode: Decode,
+) -> anyhow::Result<Vec<AnimationImageFrame>> {
+    log::debug!("Loading animation at {:?}", path);
     let data = <Vec<u8> as geng::asset::Load>::load(manager, path, &())
         .await
-        .context("when loading gif bytes")?;
-    let input = std::io::Cursor::new(data.as_slice());
-    let gif = image::codecs::gif::GifDecoder::new(input).context("when decoding gif")?;
-    let frames = gif
-        .into_frames()
-        .map(|frame| {
-            let frame = frame.unwrap();
-            let (n, d) = frame.delay().numer_denom_ms();
-            let duration = n as f32 / d as f32 / 1000.0;
-
-            let mut image = frame.into_buffer();
-            if options.frame.premultiply_alpha {
-                for pixel in image.pixels_mut() {
-                    use image::Pixel;
-                    *pixel = pixel.map_without_alpha(|x| {
-                        (x as f32 * (pixel[3] as f32 / 0xff as f32)).round() as u8
-                    });
+        .context("when loading animation bytes")?;
+    decode(&data, options).with_context(|| format!("when decoding {path:?}"))
+}
+
+/// An animation that can be loaded as an asset, e.g. in a `#[derive(geng::asset::Load)]` struct,
+/// with [AnimationOptions] as the load options.
+/// Supports the same formats as [decode_animation].
+/// The frames are stored according to the [packing](AnimationOptions::packing).
+pub enum Animation {
+    /// Every frame in its own texture.
+    Separate(Vec<AnimationFrame>),
+    /// All frames packed into a single texture.
+    Atlas(AnimationAtlas),
+}
+
+impl Animation {
+    /// Number of frames in the animation.
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Separate(frames) => frames.len(),
+            Self::Atlas(atlas) => atlas.frames.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Durations of the frames in seconds.
+    pub fn durations(&self) -> Vec<f32> {
+        match self {
+            Self::Separate(frames) => frames.iter().map(|frame| frame.duration).collect(),
+            Self::Atlas(atlas) => atlas.frames.iter().map(|frame| frame.duration).collect(),
+        }
+    }
+
+    /// Total duration of the animation in seconds.
+    pub fn duration(&self) -> f32 {
+        self.durations().into_iter().sum()
+    }
+
+    /// Create a player to animate the frames.
+    pub fn player(&self, mode: LoopMode) -> AnimationPlayer {
+        AnimationPlayer::from_durations(self.durations(), mode)
+    }
+
+    /// Prepare to draw the frame with the given index,
+    /// using a sub-rect of the texture if the frames are packed.
+    ///
+    /// # Panics
+    /// Panics if the index is out of bounds.
+    pub fn draw_frame(&self, index: usize) -> DrawTexture<'_> {
+        match self {
+            Self::Separate(frames) => DrawTexture::new(&frames[index].texture),
+            Self::Atlas(atlas) => {
+                DrawTexture::new(&atlas.texture).source_rect(atlas.frames[index].region.pixels)
+            }
+        }
+    }
+}
+
+impl geng::asset::Load for Animation {
+    type Options = AnimationOptions;
+
+    fn load(
+        manager: &geng::asset::Manager,
+        path: &std::path::Path,
+        options: &Self::Options,
+    ) -> geng::asset::Future<Self> {
+        let manager = manager.clone();
+        let path = path.to_owned();
+        let options = options.clone();
+        async move {
+            let frames = load_images(&manager, &path, &options, decode_animation).await?;
+            let ugli = manager.ugli();
+            Ok(match options.packing.columns(frames.len()) {
+                None => Self::Separate(upload_animation(ugli, frames, &options)),
+                Some(columns) => {
+                    Self::Atlas(upload_animation_atlas(ugli, &frames, columns, &options))
                 }
+            })
+        }
+        .boxed_local()
+    }
+
+    const DEFAULT_EXT: Option<&'static str> = Some("gif");
+}
+
+/// Decode GIF frames from the reader without uploading them to the GPU.
+/// The whole input is read into memory first, since decoding requires seeking.
+pub fn decode_gif_reader(
+    mut reader: impl std::io::Read,
+    options: &AnimationOptions,
+) -> anyhow::Result<Vec<AnimationImageFrame>> {
+    let mut data = Vec::new();
+    reader
+        .read_to_end(&mut data)
+        .context("when reading gif bytes")?;
+    decode_gif(&data, options)
+}
+
+/// Decode GIF frames from the bytes, e.g. embedded with [include_bytes],
+/// without uploading them to the GPU.
+pub fn decode_gif(
+    data: &[u8],
+    options: &AnimationOptions,
+) -> anyhow::Result<Vec<AnimationImageFrame>> {
+    let input = std::io::Cursor::new(data);
+    let gif = image::codecs::gif::GifDecoder::new(input).context("when decoding gif")?;
+    decode_frames(gif, options)
+}
+
+/// Format of an animated image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationFormat {
+    Gif,
+    /// Animated PNG. Still PNG images are decoded as a single frame.
+    Apng,
+    /// Animated WebP. Still WebP images are decoded as a single frame.
+    WebP,
+}
+
+impl AnimationFormat {
+    /// Detect the format from the signature at the start of the data.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match image::guess_format(data).ok()? {
+            image::ImageFormat::Gif => Some(Self::Gif),
+            image::ImageFormat::Png => Some(Self::Apng),
+            image::ImageFormat::WebP => Some(Self::WebP),
+            _ => None,
+        }
+    }
+}
+
+/// Decode frames of an animated image (GIF, APNG, or WebP) from the bytes,
+/// detecting the format from the contents, without uploading them to the GPU.
+/// Still PNG and WebP images are decoded as a single frame with zero duration.
+pub fn decode_animation(
+    data: &[u8],
+    options: &AnimationOptions,
+) -> anyhow::Result<Vec<AnimationImageFrame>> {
+    let format = AnimationFormat::detect(data).context("unsupported animation format")?;
+    let input = std::io::Cursor::new(data);
+    match format {
+        AnimationFormat::Gif => decode_gif(data, options),
+        AnimationFormat::Apng => {
+            let png = image::codecs::png::PngDecoder::new(input).context("when decoding png")?;
+            if png.is_apng().context("when decoding png")? {
+                let apng = png.apng().context("when decoding apng")?;
+                decode_frames(apng, options)
+            } else {
+                decode_still(data)
             }
+        }
+        AnimationFormat::WebP => {
+            let webp =
+                image::codecs::webp::WebPDecoder::new(input).context("when decoding webp")?;
+            if webp.has_animation() {
+                decode_frames(webp, options)
+            } else {
+                decode_still(data)
+            }
+        }
+    }
+}
 
-            let mut texture = ugli::Texture::from_image_image(manager.ugli(), image);
-            texture.set_filter(options.frame.filter);
-            texture.set_wrap_mode(options.frame.wrap_mode);
+fn decode_still(data: &[u8]) -> anyhow::Result<Vec<AnimationImageFrame>> {
+    let image = image::load_from_memory(data).context("when decoding image")?;
+    Ok(vec![AnimationImageFrame {
+        image: image.into_rgba8(),
+        duration: 0.0,
+    }])
+}
 
-            GifFrame { texture, duration }
+fn decode_frames<'a>(
+    decoder: impl image::AnimationDecoder<'a>,
+    options: &AnimationOptions,
+) -> anyhow::Result<Vec<AnimationImageFrame>> {
+    let mut frames = Vec::new();
+    for (index, frame) in decoder.into_frames().enumerate() {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(err) if options.allow_partial && !frames.is_empty() => {
+                log::warn!("Failed to decode frame {index}, keeping {index} frames: {err}");
+                break;
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("when decoding frame {index}"));
+            }
+        };
+        let (n, d) = frame.delay().numer_denom_ms();
+        let duration = n as f32 / d as f32 / 1000.0;
+        frames.push(AnimationImageFrame {
+            image: frame.into_buffer(),
+            duration,
+        });
+    }
+
+    Ok(frames)
+}
+
+/// Upload decoded frames into textures using the texture options.
+pub fn upload_animation(
+    ugli: &Ugli,
+    frames: Vec<AnimationImageFrame>,
+    options: &AnimationOptions,
+) -> Vec<AnimationFrame> {
+    frames
+        .into_iter()
+        .map(|frame| AnimationFrame {
+            texture: crate::texture::texture_from_image(ugli, frame.image, &options.frame),
+            duration: frame.duration,
+        })
+        .collect()
+}
+
+/// Pack decoded frames into a single texture with the given number of columns.
+pub fn upload_animation_atlas(
+    ugli: &Ugli,
+    frames: &[AnimationImageFrame],
+    columns: usize,
+    options: &AnimationOptions,
+) -> AnimationAtlas {
+    let (image, frames) = pack_animation(frames, columns);
+    AnimationAtlas {
+        texture: crate::texture::texture_from_image(ugli, image, &options.frame),
+        frames,
+    }
+}
+
+impl AnimationPacking {
+    /// Number of columns to pack the given number of frames into,
+    /// or `None` if the frames are not packed.
+    pub fn columns(&self, frames: usize) -> Option<usize> {
+        match *self {
+            Self::Separate => None,
+            Self::Strip => Some(frames.max(1)),
+            Self::Grid { columns } => Some(columns.max(1)),
+        }
+    }
+}
+
+/// Pack the frames into a grid with the given number of columns, row by row from the top-left.
+/// Cells have the size of the largest frame. Frames are separated by 1 pixel of padding
+/// and their edges are extruded by 1 pixel, same as [AtlasBuilder](crate::atlas::AtlasBuilder),
+/// so that linear filtering does not bleed the neighbouring frames.
+pub fn pack_animation(
+    frames: &[AnimationImageFrame],
+    columns: usize,
+) -> (image::RgbaImage, Vec<AnimationAtlasFrame>) {
+    const PADDING: u32 = 1;
+    const EXTRUDE: u32 = 1;
+
+    let columns = columns.max(1);
+    let rows = frames.len().div_ceil(columns);
+    let frame_size = frames.iter().fold(vec2(0, 0), |size, frame| {
+        let (width, height) = frame.image.dimensions();
+        vec2(size.x.max(width), size.y.max(height))
+    });
+    let cell = frame_size + vec2::splat(EXTRUDE * 2 + PADDING);
+    let size = vec2(cell.x * columns as u32, cell.y * rows as u32) + vec2::splat(PADDING);
+
+    let mut atlas = image::RgbaImage::new(size.x, size.y);
+    let regions = frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let cell_pos = vec2((i % columns) as u32 * cell.x, (i / columns) as u32 * cell.y);
+            let pos = cell_pos + vec2::splat(PADDING);
+            AnimationAtlasFrame {
+                region: crate::atlas::place(&mut atlas, &frame.image, pos, EXTRUDE),
+                duration: frame.duration,
+            }
         })
         .collect();
+    (atlas, regions)
+}
 
-    Ok(frames)
+/// How an [AnimationPlayer] continues after reaching the last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopMode {
+    /// Play once and stop at the last frame.
+    Once,
+    /// Repeat forever.
+    Loop,
+    /// Play forward and backward forever.
+    PingPong,
+    /// Play the given number of times and stop at the last frame.
+    /// Zero is treated as one.
+    LoopTimes(usize),
+}
+
+/// Something that happened during an [AnimationPlayer::update].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationEvent {
+    /// Entered the frame with the given index.
+    Frame(usize),
+    /// Completed a cycle, `loops` is the total number of completed loops.
+    Loop { loops: usize },
+    /// Stopped at the last frame.
+    Finished,
+}
+
+/// Plays an animation, e.g. [AnimationFrame]s, keeping track of the current frame.
+/// The player only works with frame indices, so that the frames can be stored anywhere.
+#[derive(Debug, Clone)]
+pub struct AnimationPlayer {
+    /// End time of each frame since the start of the animation.
+    frame_ends: Vec<f32>,
+    /// Time since the start of the current cycle, scaled by the speed.
+    /// A cycle is a single playthrough, or there and back for [LoopMode::PingPong].
+    /// Kept small so that precision is not lost during long sessions.
+    time: f32,
+    /// Number of completed cycles.
+    loops: usize,
+    frame: usize,
+    pub mode: LoopMode,
+    /// Speed multiplier of the playback.
+    pub speed: f32,
+}
+
+impl AnimationPlayer {
+    pub fn new(frames: &[AnimationFrame], mode: LoopMode) -> Self {
+        Self::from_durations(frames.iter().map(|frame| frame.duration), mode)
+    }
+
+    /// Create a player for frames with the given durations in seconds.
+    pub fn from_durations(durations: impl IntoIterator<Item = f32>, mode: LoopMode) -> Self {
+        let frame_ends = durations
+            .into_iter()
+            .scan(0.0, |time, duration| {
+                *time += duration.max(0.0);
+                Some(*time)
+            })
+            .collect();
+        Self {
+            frame_ends,
+            time: 0.0,
+            loops: 0,
+            frame: 0,
+            mode,
+            speed: 1.0,
+        }
+    }
+
+    pub fn with_speed(self, speed: f32) -> Self {
+        Self { speed, ..self }
+    }
+
+    /// Number of frames in the animation.
+    pub fn frames(&self) -> usize {
+        self.frame_ends.len()
+    }
+
+    /// Duration of a single playthrough in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frame_ends.last().copied().unwrap_or(0.0)
+    }
+
+    /// Index of the current frame. Always 0 if the animation has no frames.
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Number of completed loops. For [LoopMode::PingPong] a loop goes there and back.
+    pub fn loops(&self) -> usize {
+        self.loops
+    }
+
+    /// Time in seconds since the start of the current playthrough.
+    pub fn time(&self) -> f32 {
+        let duration = self.duration();
+        if self.is_finished() {
+            return duration;
+        }
+        match self.mode {
+            LoopMode::PingPong if self.time >= duration => duration * 2.0 - self.time,
+            _ => self.time,
+        }
+    }
+
+    /// Whether the animation has stopped at the last frame.
+    /// Looping animations never finish.
+    pub fn is_finished(&self) -> bool {
+        self.max_loops().is_some_and(|max| self.loops >= max)
+    }
+
+    /// Advance the animation by `delta_time` seconds, scaled by the speed.
+    /// Returns the events in the order they happened, including every frame passed on the way.
+    /// If the update spans several whole cycles, the frames of the skipped cycles
+    /// are not reported, and a single [AnimationEvent::Loop] reports the total instead.
+    /// Playing backwards (with a negative speed) only reports the frame it lands on.
+    pub fn update(&mut self, delta_time: f32) -> Vec<AnimationEvent> {
+        let delta = delta_time * self.speed;
+        let cycle = self.cycle();
+        let forward = delta > 0.0 && delta.is_finite();
+        if self.is_finished() || cycle <= 0.0 || !forward {
+            let frame = self.set_time(self.loops, self.time + delta);
+            return frame.map(AnimationEvent::Frame).into_iter().collect();
+        }
+
+        let mut events = Vec::new();
+        let mut remaining = delta;
+
+        // Skip all whole cycles but the last one
+        let mut skip = ((remaining / cycle).floor() as usize).saturating_sub(1);
+        if let Some(max) = self.max_loops() {
+            skip = skip.min(max - 1 - self.loops);
+        }
+        if skip > 0 {
+            self.loops += skip;
+            remaining -= skip as f32 * cycle;
+            events.push(AnimationEvent::Loop { loops: self.loops });
+        }
+
+        let boundaries = self.frame_boundaries();
+        while remaining > 0.0 {
+            let next = boundaries
+                .iter()
+                .copied()
+                .find(|&time| time > self.time)
+                .unwrap_or(cycle);
+            if self.time + remaining < next {
+                self.time += remaining;
+                break;
+            }
+            remaining -= next - self.time;
+
+            if next < cycle {
+                self.time = next;
+            } else {
+                self.time = 0.0;
+                self.loops += 1;
+                events.push(AnimationEvent::Loop { loops: self.loops });
+            }
+            let frame = self.current_frame();
+            if frame != self.frame {
+                self.frame = frame;
+                events.push(AnimationEvent::Frame(frame));
+            }
+            if self.is_finished() {
+                events.push(AnimationEvent::Finished);
+                break;
+            }
+        }
+        events
+    }
+
+    /// Jump to the given time since the start of the animation, counting all repeats.
+    /// Returns the index of the new frame if it has changed.
+    pub fn seek(&mut self, time: f32) -> Option<usize> {
+        self.set_time(0, time)
+    }
+
+    /// Jump to the start of the frame with the given index in the first playthrough.
+    /// Returns the index of the new frame if it has changed.
+    pub fn seek_frame(&mut self, frame: usize) -> Option<usize> {
+        let time = match frame.checked_sub(1) {
+            None => 0.0,
+            Some(previous) => self
+                .frame_ends
+                .get(previous)
+                .copied()
+                .unwrap_or(self.duration()),
+        };
+        self.set_time(0, time)
+    }
+
+    /// Start playing from the first frame.
+    pub fn restart(&mut self) -> Option<usize> {
+        self.set_time(0, 0.0)
+    }
+
+    /// Duration of a single cycle.
+    fn cycle(&self) -> f32 {
+        match self.mode {
+            LoopMode::PingPong => self.duration() * 2.0,
+            _ => self.duration(),
+        }
+    }
+
+    /// Number of cycles after which the animation stops.
+    fn max_loops(&self) -> Option<usize> {
+        match self.mode {
+            LoopMode::Once => Some(1),
+            LoopMode::LoopTimes(times) => Some(times.max(1)),
+            LoopMode::Loop | LoopMode::PingPong => None,
+        }
+    }
+
+    /// Set the time relative to the start of the given cycle,
+    /// wrapping it into the range of a single cycle.
+    fn set_time(&mut self, mut loops: usize, mut time: f32) -> Option<usize> {
+        let cycle = self.cycle();
+        if cycle <= 0.0 {
+            (loops, time) = (0, 0.0);
+        } else {
+            let wrapped = (time / cycle).floor();
+            let rewound = (-wrapped).max(0.0) as usize;
+            if rewound > loops {
+                // Rewound before the start
+                (loops, time) = (0, 0.0);
+            } else {
+                loops = (loops - rewound).saturating_add(wrapped.max(0.0) as usize);
+                time = time.rem_euclid(cycle);
+            }
+            if let Some(max) = self.max_loops().filter(|&max| loops >= max) {
+                (loops, time) = (max, 0.0);
+            }
+        }
+        self.loops = loops;
+        self.time = time;
+
+        let frame = self.current_frame();
+        (frame != self.frame).then(|| {
+            self.frame = frame;
+            frame
+        })
+    }
+
+    /// Find the frame at the current time, the last one if finished.
+    fn current_frame(&self) -> usize {
+        let last = self.frames().saturating_sub(1);
+        if self.is_finished() {
+            return last;
+        }
+        let duration = self.duration();
+        let index = if self.mode == LoopMode::PingPong && self.time >= duration {
+            // Going backwards, so the frame starting at the time is the one before it
+            let time = duration * 2.0 - self.time;
+            self.frame_ends.partition_point(|&end| end < time)
+        } else {
+            self.frame_ends.partition_point(|&end| end <= self.time)
+        };
+        index.min(last)
+    }
+
+    /// Times within a cycle when the frame changes, in increasing order.
+    fn frame_boundaries(&self) -> Vec<f32> {
+        let inner = &self.frame_ends[..self.frames().saturating_sub(1)];
+        let mut times = inner.to_vec();
+        if self.mode == LoopMode::PingPong {
+            let duration = self.duration();
+            times.extend(inner.iter().rev().map(|end| duration * 2.0 - end));
+        }
+        times
+    }
+}
+
+#[test]
+fn test_gif_player() {
+    use AnimationEvent::*;
+
+    let durations = [0.1, 0.2, 0.3];
+
+    let mut player = AnimationPlayer::from_durations(durations, LoopMode::Once);
+    assert_eq!(player.frames(), 3);
+    assert_eq!(player.frame(), 0);
+    assert!(player.update(0.05).is_empty());
+    assert_eq!(player.update(0.1), [Frame(1)]);
+    assert_eq!(player.update(0.2), [Frame(2)]);
+    assert!(!player.is_finished());
+    assert_eq!(player.update(10.0), [Loop { loops: 1 }, Finished]);
+    assert!(player.is_finished());
+    assert_eq!(player.frame(), 2);
+    assert!(player.update(1.0).is_empty());
+
+    // Every frame passed by a single update is reported
+    let mut player = AnimationPlayer::from_durations(durations, LoopMode::Loop);
+    assert_eq!(
+        player.update(0.65),
+        [Frame(1), Frame(2), Loop { loops: 1 }, Frame(0)]
+    );
+    assert!((player.time() - 0.05).abs() < 1e-5);
+    let mut player = AnimationPlayer::from_durations(durations, LoopMode::Once);
+    assert_eq!(player.update(0.5), [Frame(1), Frame(2)]);
+
+    // A single frame still reports the loops
+    let mut single = AnimationPlayer::from_durations([0.5], LoopMode::Loop);
+    assert_eq!(single.update(0.5), [Loop { loops: 1 }]);
+    assert_eq!(single.update(1.2), [Loop { loops: 2 }, Loop { loops: 3 }]);
+    assert_eq!(single.loops(), 3);
+
+    let mut player = AnimationPlayer::from_durations(durations, LoopMode::Loop);
+    assert_eq!(player.seek(0.65), None);
+    assert_eq!(player.frame(), 0);
+    assert_eq!(player.seek(0.95), Some(2));
+    assert!(!player.is_finished());
+
+    let mut player = AnimationPlayer::from_durations(durations, LoopMode::PingPong).with_speed(2.0);
+    assert_eq!(player.update(0.35), [Frame(1), Frame(2)]);
+    // Going backwards after 0.6
+    assert_eq!(player.update(0.15), [Frame(1)]);
+    assert_eq!(player.update(0.075), [Frame(0)]);
+    assert_eq!(player.update(0.05), [Loop { loops: 1 }]);
+
+    let mut player = AnimationPlayer::from_durations(durations, LoopMode::LoopTimes(2));
+    assert_eq!(player.seek(0.8), Some(1));
+    assert!(!player.is_finished());
+    assert_eq!(player.seek(1.5), Some(2));
+    assert!(player.is_finished());
+
+    assert_eq!(player.seek_frame(1), Some(1));
+    assert!((player.time() - 0.1).abs() < 1e-6);
+    assert_eq!(player.restart(), Some(0));
+
+    // Small steps are not lost after a long time
+    let mut player = AnimationPlayer::from_durations(durations, LoopMode::Loop);
+    player.seek(1e8);
+    let frames: std::collections::HashSet<usize> = (0..12)
+        .flat_map(|_| player.update(0.05))
+        .filter_map(|event| match event {
+            Frame(frame) => Some(frame),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(frames.len(), 3);
+    assert!(player.loops() > 1000);
+
+    let mut empty = AnimationPlayer::from_durations([], LoopMode::Loop);
+    assert!(empty.update(1.0).is_empty());
+    assert_eq!(empty.frame(), 0);
+}
+
+#[test]
+fn test_load_options() {
+    // `#[load(options(...))]` fields are written with the gif options
+    fn load_options<T: geng::asset::Load<Options = GifOptions>>() {}
+    load_options::<Animation>();
+}
+
+#[test]
+fn test_decode_gif() {
+    // Encode a small animation with noisy frames, so that the image data is not too short
+    let mut data = Vec::new();
+    {
+        let mut encoder = image::codecs::gif::GifEncoder::new(&mut data);
+        let frames = (0..3).map(|i| {
+            let image = image::RgbaImage::from_fn(16, 16, |x, y| {
+                let value = ((x * 7 + y * 13 + i * 5) % 4 * 80) as u8;
+                image::Rgba([value, 255 - value, 0, 255])
+            });
+            let delay = image::Delay::from_numer_denom_ms(100 * (i + 1), 1);
+            image::Frame::from_parts(image, 0, 0, delay)
+        });
+        encoder.encode_frames(frames).unwrap();
+    }
+
+    let options = AnimationOptions::default();
+    let frames = decode_gif(&data, &options).unwrap();
+    assert_eq!(frames.len(), 3);
+    for (i, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.image.dimensions(), (16, 16));
+        assert!((frame.duration - 0.1 * (i + 1) as f32).abs() < 1e-5);
+    }
+    let from_reader = decode_gif_reader(data.as_slice(), &options).unwrap();
+    assert_eq!(from_reader.len(), 3);
+
+    // Cut off the end of the last frame
+    let truncated = &data[..data.len() - 8];
+    let err = decode_gif(truncated, &options).unwrap_err();
+    assert!(format!("{err:#}").contains("frame 2"), "{err:#}");
+
+    let options = AnimationOptions {
+        allow_partial: true,
+        ..default()
+    };
+    let frames = decode_gif(truncated, &options).unwrap();
+    assert_eq!(frames.len(), 2);
+
+    assert!(decode_gif(b"not a gif", &options).is_err());
+}
+
+#[test]
+fn test_pack_animation() {
+    let frames: Vec<AnimationImageFrame> = (0..5)
+        .map(|i| AnimationImageFrame {
+            image: image::RgbaImage::from_pixel(4, 2, image::Rgba([i * 50, 0, 0, 255])),
+            duration: 0.1,
+        })
+        .collect();
+
+    // Cells of 4x2 plus 1 pixel of extrusion on each side and 1 pixel of padding
+    let (image, regions) = pack_animation(&frames, AnimationPacking::Strip.columns(5).unwrap());
+    assert_eq!(image.dimensions(), (36, 6));
+    assert_eq!(
+        regions[1].region.pixels,
+        Aabb2::point(vec2(9, 2)).extend_positive(vec2(4, 2))
+    );
+
+    let (image, regions) = pack_animation(&frames, 2);
+    let size = vec2(15, 16);
+    assert_eq!(image.dimensions(), (size.x, size.y));
+    // The first row in the image is the top row in the texture
+    assert_eq!(regions[0].region.pixels.min, vec2(2, 12));
+    assert_eq!(regions[3].region.pixels.min, vec2(9, 7));
+    assert_eq!(regions[4].region.pixels.min, vec2(2, 2));
+    assert_eq!(
+        regions[4].region.uv.min,
+        vec2(2.0 / size.x as f32, 2.0 / size.y as f32)
+    );
+    for (i, frame) in regions.iter().enumerate() {
+        crate::atlas::assert_extruded(&image, &frame.region, 1, [i as u8 * 50, 0, 0, 255]);
+        // Padding between the extruded frames stays transparent
+        let pixels = frame.region.pixels.extend_uniform(1);
+        let padding = image.get_pixel(pixels.max.x as u32, size.y - 1 - pixels.min.y as u32);
+        assert_eq!(padding.0, [0; 4]);
+        assert_eq!(frame.duration, 0.1);
+    }
+
+    assert_eq!(AnimationPacking::Separate.columns(5), None);
+}
+
+#[test]
+fn test_decode_animation() {
+    let image = image::RgbaImage::from_pixel(3, 2, image::Rgba([10, 20, 30, 255]));
+    let options = AnimationOptions::default();
+
+    let png = crate::texture::encode_png(&image).unwrap();
+    assert_eq!(AnimationFormat::detect(&png), Some(AnimationFormat::Apng));
+    let frames = decode_animation(&png, &options).unwrap();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].image, image);
+
+    let mut webp = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut webp, image::ImageFormat::WebP).unwrap();
+    let webp = webp.into_inner();
+    assert_eq!(AnimationFormat::detect(&webp), Some(AnimationFormat::WebP));
+    let frames = decode_animation(&webp, &options).unwrap();
+    assert_eq!(frames.len(), 1);
+    assert_eq!(frames[0].image, image);
+
+    let mut gif = Vec::new();
+    {
+        let mut encoder = image::codecs::gif::GifEncoder::new(&mut gif);
+        let frames = (0..2).map(|_| image::Frame::new(image.clone()));
+        encoder.encode_frames(frames).unwrap();
+    }
+    assert_eq!(AnimationFormat::detect(&gif), Some(AnimationFormat::Gif));
+    assert_eq!(decode_animation(&gif, &options).unwrap().len(), 2);
+
+    assert_eq!(AnimationFormat::detect(b"not an image"), None);
+    assert!(decode_animation(b"not an image", &options).is_err());
 }
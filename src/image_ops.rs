@@ -0,0 +1,225 @@
+use geng::prelude::*;
+
+use crate::conversions::Vec2RealConversions;
+
+/// Location of a trimmed image inside of the original one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trim {
+    /// Size of the image before trimming.
+    pub original_size: vec2<usize>,
+    /// Pixel rect of the trimmed image inside of the original,
+    /// with the origin in the bottom-left corner, same as textures.
+    pub rect: Aabb2<usize>,
+}
+
+impl Trim {
+    /// Offset of the top-left corner of the trimmed image in image coordinates,
+    /// i.e. with the origin in the top-left corner.
+    pub fn image_offset(&self) -> vec2<usize> {
+        vec2(self.rect.min.x, self.original_size.y - self.rect.max.y)
+    }
+
+    /// Given the world `target` of the original image, compute the target of the trimmed one.
+    pub fn target(&self, target: Aabb2<f32>) -> Aabb2<f32> {
+        if self.original_size.x == 0 || self.original_size.y == 0 {
+            return Aabb2::point(target.min);
+        }
+        let scale = target.size() / self.original_size.as_f32();
+        self.rect
+            .map_bounds(|pos| target.min + pos.as_f32() * scale)
+    }
+}
+
+/// Cut off the fully transparent borders of the image.
+/// Returns the trimmed image and its location in the original.
+/// A fully transparent image is trimmed to an empty one.
+pub fn trim(image: &image::RgbaImage) -> (image::RgbaImage, Trim) {
+    let (width, height) = image.dimensions();
+    let original_size = vec2(width as usize, height as usize);
+
+    let mut bounds: Option<Aabb2<u32>> = None;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        if pixel.0[3] == 0 {
+            continue;
+        }
+        bounds = Some(match bounds {
+            None => Aabb2::point(vec2(x, y)),
+            Some(bounds) => Aabb2 {
+                min: vec2(bounds.min.x.min(x), bounds.min.y.min(y)),
+                max: vec2(bounds.max.x.max(x), bounds.max.y.max(y)),
+            },
+        });
+    }
+
+    let Some(bounds) = bounds else {
+        let trim = Trim {
+            original_size,
+            rect: Aabb2::ZERO,
+        };
+        return (image::RgbaImage::new(0, 0), trim);
+    };
+
+    // Bounds are inclusive in image coordinates
+    let size = bounds.max - bounds.min + vec2::splat(1);
+    let trimmed =
+        image::imageops::crop_imm(image, bounds.min.x, bounds.min.y, size.x, size.y).to_image();
+    let min = vec2(bounds.min.x, height - 1 - bounds.max.y);
+    let rect = Aabb2::point(min.map(|x| x as usize)).extend_positive(size.map(|x| x as usize));
+    (
+        trimmed,
+        Trim {
+            original_size,
+            rect,
+        },
+    )
+}
+
+/// Generate an outline around the opaque pixels of the image, `thickness` pixels wide.
+/// The result contains only the outline and is larger than the original by `thickness`
+/// on each side, so that the outline is not cut off.
+pub fn outline(image: &image::RgbaImage, color: Rgba<u8>, thickness: u32) -> image::RgbaImage {
+    let (width, height) = image.dimensions();
+    let t = thickness as i64;
+    let opaque = |x: i64, y: i64| {
+        let (x, y) = (x - t, y - t);
+        x >= 0
+            && y >= 0
+            && x < width as i64
+            && y < height as i64
+            && image.get_pixel(x as u32, y as u32).0[3] > 0
+    };
+
+    let (out_width, out_height) = (width + thickness * 2, height + thickness * 2);
+    let mut filled: Vec<bool> = (0..out_height as i64)
+        .flat_map(|y| (0..out_width as i64).map(move |x| (x, y)))
+        .map(|(x, y)| opaque(x, y))
+        .collect();
+    let original = filled.clone();
+
+    // Grow the opaque area one pixel at a time
+    let index = |x: i64, y: i64| (y * out_width as i64 + x) as usize;
+    for _ in 0..thickness {
+        let previous = filled.clone();
+        for y in 0..out_height as i64 {
+            for x in 0..out_width as i64 {
+                let neighbour = [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                    .into_iter()
+                    .map(|(dx, dy)| (x + dx, y + dy))
+                    .filter(|&(x, y)| {
+                        x >= 0 && y >= 0 && x < out_width as i64 && y < out_height as i64
+                    })
+                    .any(|(x, y)| previous[index(x, y)]);
+                if neighbour {
+                    filled[index(x, y)] = true;
+                }
+            }
+        }
+    }
+
+    let color = image::Rgba([color.r, color.g, color.b, color.a]);
+    image::RgbaImage::from_fn(out_width, out_height, |x, y| {
+        let i = index(x as i64, y as i64);
+        if filled[i] && !original[i] {
+            color
+        } else {
+            image::Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+/// Draw the image over its [outline].
+/// The result is larger than the original by `thickness` on each side.
+pub fn with_outline(image: &image::RgbaImage, color: Rgba<u8>, thickness: u32) -> image::RgbaImage {
+    let mut result = outline(image, color, thickness);
+    image::imageops::overlay(&mut result, image, thickness as i64, thickness as i64);
+    result
+}
+
+/// Replace the colors of the image according to the palette of `(from, to)` pairs.
+/// Colors are matched exactly, including alpha. Other colors are left unchanged.
+pub fn palette_swap(image: &mut image::RgbaImage, palette: &[(Rgba<u8>, Rgba<u8>)]) {
+    recolor(image, |color| {
+        palette
+            .iter()
+            .find(|(from, _)| *from == color)
+            .map_or(color, |&(_, to)| to)
+    });
+}
+
+/// Change the color of every pixel in the image.
+pub fn recolor(image: &mut image::RgbaImage, f: impl Fn(Rgba<u8>) -> Rgba<u8>) {
+    for pixel in image.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let color = f(Rgba { r, g, b, a });
+        pixel.0 = [color.r, color.g, color.b, color.a];
+    }
+}
+
+/// Multiply every pixel in the image by the color.
+pub fn tint(image: &mut image::RgbaImage, color: Rgba<f32>) {
+    let mul = |value: u8, t: f32| (value as f32 * t).round().clamp(0.0, 255.0) as u8;
+    recolor(image, |c| Rgba {
+        r: mul(c.r, color.r),
+        g: mul(c.g, color.g),
+        b: mul(c.b, color.b),
+        a: mul(c.a, color.a),
+    });
+}
+
+/// Replace the color of every pixel while keeping its alpha, e.g. for a hit flash.
+pub fn silhouette(image: &mut image::RgbaImage, color: Rgba<u8>) {
+    recolor(image, |c| Rgba {
+        a: (c.a as u32 * color.a as u32 / 255) as u8,
+        ..color
+    });
+}
+
+#[test]
+fn test_image_ops() {
+    let clear = image::Rgba([0, 0, 0, 0]);
+    let red = image::Rgba([255, 0, 0, 255]);
+
+    // A 2x1 opaque block at (3, 1) in a 6x4 image
+    let mut image = image::RgbaImage::from_pixel(6, 4, clear);
+    image.put_pixel(3, 1, red);
+    image.put_pixel(4, 1, red);
+
+    let (trimmed, trim) = trim(&image);
+    assert_eq!(trimmed.dimensions(), (2, 1));
+    assert_eq!(trim.image_offset(), vec2(3, 1));
+    assert_eq!(
+        trim.rect,
+        Aabb2::point(vec2(3, 2)).extend_positive(vec2(2, 1))
+    );
+    let target = trim.target(Aabb2::ZERO.extend_positive(vec2(12.0, 8.0)));
+    assert_eq!(
+        target,
+        Aabb2::point(vec2(6.0, 4.0)).extend_positive(vec2(4.0, 2.0))
+    );
+
+    let (empty, trim) = self::trim(&image::RgbaImage::from_pixel(3, 3, clear));
+    assert_eq!(empty.dimensions(), (0, 0));
+    assert_eq!(trim.rect, Aabb2::ZERO);
+
+    let black = Rgba::new(0, 0, 0, 255);
+    let outlined = outline(&trimmed, black, 1);
+    assert_eq!(outlined.dimensions(), (4, 3));
+    let opaque = outlined.pixels().filter(|p| p.0[3] > 0).count();
+    // Sides only, no diagonal corners
+    assert_eq!(opaque, 6);
+    assert_eq!(outlined.get_pixel(1, 1).0, [0, 0, 0, 0]);
+    assert_eq!(with_outline(&trimmed, black, 1).get_pixel(1, 1).0, red.0);
+
+    let mut swapped = trimmed.clone();
+    palette_swap(
+        &mut swapped,
+        &[(Rgba::new(255, 0, 0, 255), Rgba::new(0, 0, 255, 255))],
+    );
+    assert_eq!(swapped.get_pixel(0, 0).0, [0, 0, 255, 255]);
+
+    tint(&mut swapped, Rgba::new(1.0, 1.0, 0.5, 0.5));
+    assert_eq!(swapped.get_pixel(0, 0).0, [0, 0, 128, 128]);
+
+    silhouette(&mut swapped, Rgba::new(255, 255, 255, 255));
+    assert_eq!(swapped.get_pixel(0, 0).0, [255, 255, 255, 128]);
+}
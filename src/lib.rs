@@ -1,13 +1,19 @@
 //! A collection of utilities to use with [geng](https://github.com/geng-engine/geng) engine.
 
+/// Packing images into a texture atlas.
+pub mod atlas;
 /// Bounded value.
 pub mod bounded;
+/// Conversions between color formats.
+pub mod color;
 /// Handy conversion between types.
 pub mod conversions;
 /// Some primitive geometric shapes.
 pub mod geometry;
-/// GIF loader.
+/// Animated image (GIF, APNG, WebP) loader.
 pub mod gif;
+/// CPU operations on images: outlines, recoloring, trimming.
+pub mod image_ops;
 /// Different interpolation algorithms.
 pub mod interpolation;
 /// A possibly more convenient key enum.
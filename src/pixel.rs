@@ -47,3 +47,110 @@ fn camera_world_to_screen(
         (pos.y + 1.0) / 2.0 * framebuffer_size.y,
     )
 }
+
+/// The largest integer scale such that the canvas fits into the window, but at least 1.
+pub fn integer_scale(canvas_size: vec2<usize>, window_size: vec2<usize>) -> usize {
+    let scale = |canvas: usize, window: usize| window.checked_div(canvas).unwrap_or(usize::MAX);
+    scale(canvas_size.x, window_size.x)
+        .min(scale(canvas_size.y, window_size.y))
+        .max(1)
+}
+
+/// The rect in window pixels that the canvas upscaled by [integer_scale] occupies,
+/// centered in the window.
+pub fn integer_scaled_rect(canvas_size: vec2<usize>, window_size: vec2<usize>) -> Aabb2<i64> {
+    let scale = integer_scale(canvas_size, window_size);
+    let size = canvas_size.map(|x| (x * scale) as i64);
+    let window_size = window_size.map(|x| x as i64);
+    // Offset may be negative if the canvas does not fit even at scale 1
+    let offset = (window_size - size).map(|x| x.div_euclid(2));
+    Aabb2::point(offset).extend_positive(size)
+}
+
+/// A low resolution render target drawn onto the window with integer upscaling
+/// and letterboxing, to keep pixel art crisp.
+pub struct PixelCanvas {
+    /// The texture to render the game into.
+    pub texture: ugli::Texture,
+    /// Color of the bars around the canvas.
+    pub letterbox_color: Rgba<f32>,
+}
+
+impl PixelCanvas {
+    pub fn new(ugli: &Ugli, size: vec2<usize>) -> Self {
+        Self {
+            texture: crate::texture::new_texture(ugli, size),
+            letterbox_color: Rgba::BLACK,
+        }
+    }
+
+    pub fn with_letterbox_color(self, letterbox_color: Rgba<f32>) -> Self {
+        Self {
+            letterbox_color,
+            ..self
+        }
+    }
+
+    /// Size of the canvas in pixels.
+    pub fn size(&self) -> vec2<usize> {
+        self.texture.size()
+    }
+
+    /// Resize the canvas, clearing its contents.
+    pub fn resize(&mut self, size: vec2<usize>, ugli: &Ugli) {
+        crate::texture::update_texture_size(&mut self.texture, size, ugli);
+    }
+
+    /// A framebuffer to render into the canvas.
+    pub fn framebuffer<'a>(&'a mut self, ugli: &Ugli) -> ugli::Framebuffer<'a> {
+        crate::texture::attach_texture(&mut self.texture, ugli)
+    }
+
+    /// The current integer scale of the canvas in the window.
+    pub fn scale(&self, window_size: vec2<usize>) -> usize {
+        integer_scale(self.size(), window_size)
+    }
+
+    /// The rect in window pixels that the canvas occupies.
+    pub fn window_rect(&self, window_size: vec2<usize>) -> Aabb2<f32> {
+        integer_scaled_rect(self.size(), window_size).map_bounds(|pos| pos.as_f32())
+    }
+
+    /// Convert a position in the window (e.g. the cursor position)
+    /// into the canvas pixel coordinates. Both have the origin in the bottom-left corner.
+    /// The result lies outside the canvas if the position is on the letterbox.
+    pub fn window_to_canvas(&self, pos: vec2<f32>, window_size: vec2<usize>) -> vec2<f32> {
+        let rect = self.window_rect(window_size);
+        (pos - rect.min) / self.scale(window_size) as f32
+    }
+
+    /// Convert a position in the window into the canvas pixel it points at.
+    /// Returns `None` if the position is outside the canvas.
+    pub fn window_to_pixel(&self, pos: vec2<f32>, window_size: vec2<usize>) -> Option<vec2<usize>> {
+        let pos = self.window_to_canvas(pos, window_size).map(f32::floor);
+        let size = self.size().as_f32();
+        (pos.x >= 0.0 && pos.y >= 0.0 && pos.x < size.x && pos.y < size.y)
+            .then(|| pos.map(|x| x as usize))
+    }
+
+    /// Draw the canvas onto the window framebuffer, filling the rest with the letterbox color.
+    pub fn draw(&self, geng: &Geng, framebuffer: &mut ugli::Framebuffer) {
+        ugli::clear(framebuffer, Some(self.letterbox_color), None, None);
+        let target = self.window_rect(framebuffer.size());
+        crate::texture::DrawTexture::new(&self.texture)
+            .fit(target, vec2::splat(0.5))
+            .draw(&geng::PixelPerfectCamera, geng, framebuffer);
+    }
+}
+
+#[test]
+fn test_integer_scale() {
+    let canvas = vec2(320, 180);
+    assert_eq!(integer_scale(canvas, vec2(1920, 1080)), 6);
+    assert_eq!(integer_scale(canvas, vec2(1920, 1000)), 5);
+    assert_eq!(integer_scale(canvas, vec2(100, 100)), 1);
+
+    let rect = integer_scaled_rect(canvas, vec2(1920, 1000));
+    assert_eq!(rect.size(), vec2(1600, 900));
+    assert_eq!(rect.min, vec2(160, 50));
+}
@@ -0,0 +1,227 @@
+use geng::prelude::*;
+
+use super::DrawTexture;
+
+/// Vertices of sprites sharing the same texture, drawn in a single draw call.
+pub struct SpriteBatchPart<'a> {
+    pub texture: &'a ugli::Texture,
+    /// A list of triangles in world space.
+    pub vertices: Vec<draw2d::TexturedVertex>,
+}
+
+struct Sprite<'a> {
+    sprite: DrawTexture<'a>,
+    layer: i32,
+}
+
+/// Collects many [DrawTexture]s to draw them with as few draw calls as possible.
+/// Sprites are sorted by layer, keeping the order they were pushed in within the same layer,
+/// and consecutive sprites with the same texture are drawn in a single call.
+/// So, push sprites with the same texture one after another to get fewer draw calls,
+/// or [sort by texture](Self::sort_by_texture) if the draw order within a layer does not matter.
+///
+/// All sprites are drawn with the batch's [parameters](Self::parameters),
+/// individual [DrawTexture::parameters] are ignored.
+pub struct SpriteBatch<'a> {
+    sprites: Vec<Sprite<'a>>,
+    sort_by_texture: bool,
+    /// Parameters used for drawing.
+    pub parameters: ugli::DrawParameters,
+}
+
+impl Default for SpriteBatch<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> SpriteBatch<'a> {
+    pub fn new() -> Self {
+        Self {
+            sprites: Vec::new(),
+            sort_by_texture: false,
+            parameters: ugli::DrawParameters {
+                blend_mode: Some(super::blend::alpha()),
+                ..default()
+            },
+        }
+    }
+
+    pub fn with_parameters(self, parameters: ugli::DrawParameters) -> Self {
+        Self { parameters, ..self }
+    }
+
+    /// Whether to also sort the sprites by texture within a layer to get fewer draw calls.
+    /// Only use it when the order does not matter, e.g. for opaque or additive sprites.
+    pub fn sort_by_texture(self, sort_by_texture: bool) -> Self {
+        Self {
+            sort_by_texture,
+            ..self
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+
+    /// Add a sprite on the layer 0.
+    pub fn push(&mut self, sprite: DrawTexture<'a>) {
+        self.push_layered(sprite, 0);
+    }
+
+    /// Add a sprite on the given layer. Lower layers are drawn first.
+    /// Sprites on the same layer are drawn in the order they were pushed in.
+    pub fn push_layered(&mut self, sprite: DrawTexture<'a>, layer: i32) {
+        self.sprites.push(Sprite { sprite, layer });
+    }
+
+    /// Generate vertices on the CPU grouped into parts that can be drawn in one call each.
+    pub fn parts(&mut self) -> Vec<SpriteBatchPart<'a>> {
+        let texture = |sprite: &Sprite<'a>| sprite.sprite.texture as *const ugli::Texture;
+        let runs = if self.sort_by_texture {
+            group(
+                &mut self.sprites,
+                |sprite| (sprite.layer, texture(sprite)),
+                texture,
+            )
+        } else {
+            group(&mut self.sprites, |sprite| sprite.layer, texture)
+        };
+        runs.into_iter()
+            .map(|run| {
+                let sprites = &self.sprites[run];
+                SpriteBatchPart {
+                    texture: sprites[0].sprite.texture,
+                    vertices: sprites
+                        .iter()
+                        .flat_map(|sprite| sprite.sprite.world_triangles())
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    /// Draw all sprites and clear the batch.
+    pub fn draw(
+        &mut self,
+        camera: &impl geng::AbstractCamera2d,
+        geng: &Geng,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        for part in self.parts() {
+            super::program::draw_textured(
+                &part.vertices,
+                ugli::DrawMode::Triangles,
+                part.texture,
+                Rgba::WHITE,
+                mat3::identity(),
+                &self.parameters,
+                camera,
+                geng,
+                framebuffer,
+            );
+        }
+        self.clear();
+    }
+}
+
+/// Sort the items by `order`, keeping the order of equal items,
+/// and find the ranges of consecutive items with the same key.
+fn group<T, O: Ord, K: PartialEq>(
+    items: &mut [T],
+    order: impl Fn(&T) -> O,
+    key: impl Fn(&T) -> K,
+) -> Vec<std::ops::Range<usize>> {
+    // Stable sort
+    items.sort_by_key(&order);
+
+    let mut runs: Vec<std::ops::Range<usize>> = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if key(&items[run.start]) == key(item) => run.end = i + 1,
+            _ => runs.push(i..i + 1),
+        }
+    }
+    runs
+}
+
+/// Transform a quad (as in [crate::geometry::unit_quad]) into two triangles,
+/// multiplying vertex colors by the `color`.
+pub fn transform_quad(
+    quad: [draw2d::TexturedVertex; 4],
+    transform: mat3<f32>,
+    color: Rgba<f32>,
+) -> [draw2d::TexturedVertex; 6] {
+    let quad = quad.map(|vertex| transform_vertex(vertex, transform, color));
+    [0, 1, 2, 0, 2, 3].map(|i| quad[i])
+}
+
+pub(super) fn transform_vertex(
+    vertex: draw2d::TexturedVertex,
+    transform: mat3<f32>,
+    color: Rgba<f32>,
+) -> draw2d::TexturedVertex {
+    let pos = transform * vertex.a_pos.extend(1.0);
+    let c = vertex.a_color;
+    draw2d::TexturedVertex {
+        a_pos: pos.xy() / pos.z,
+        a_color: Rgba::new(c.r * color.r, c.g * color.g, c.b * color.b, c.a * color.a),
+        a_vt: vertex.a_vt,
+    }
+}
+
+#[test]
+fn test_transform_quad() {
+    let color = Rgba::new(1.0, 0.5, 0.0, 0.5);
+    let transform = mat3::translate(vec2(10.0, 20.0)) * mat3::scale(vec2(2.0, 3.0));
+    let vertices = transform_quad(crate::geometry::unit_quad(), transform, color);
+
+    let positions = vertices.map(|v| v.a_pos);
+    assert_eq!(
+        positions,
+        [
+            vec2(8.0, 17.0),
+            vec2(12.0, 17.0),
+            vec2(12.0, 23.0),
+            vec2(8.0, 17.0),
+            vec2(12.0, 23.0),
+            vec2(8.0, 23.0),
+        ]
+    );
+    assert!(vertices.iter().all(|v| v.a_color == color));
+    assert_eq!(vertices[2].a_vt, vec2(1.0, 1.0));
+}
+
+#[test]
+fn test_group() {
+    // (layer, texture, name)
+    let mut items = [
+        (1, 'a', 0),
+        (0, 'b', 1),
+        (0, 'b', 2),
+        (1, 'a', 3),
+        (0, 'a', 4),
+        (0, 'b', 5),
+        (1, 'b', 6),
+    ];
+    let mut sorted = items;
+    let runs = group(&mut items, |item| item.0, |item| item.1);
+
+    let names: Vec<i32> = items.iter().map(|item| item.2).collect();
+    assert_eq!(names, [1, 2, 4, 5, 0, 3, 6]);
+    assert_eq!(runs, [0..2, 2..3, 3..4, 4..6, 6..7]);
+
+    // Sorted by texture within a layer, alternating textures are merged
+    let runs = group(&mut sorted, |item| (item.0, item.1), |item| item.1);
+    let names: Vec<i32> = sorted.iter().map(|item| item.2).collect();
+    assert_eq!(names, [4, 1, 2, 5, 0, 3, 6]);
+    assert_eq!(runs, [0..1, 1..4, 4..6, 6..7]);
+}
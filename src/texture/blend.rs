@@ -0,0 +1,97 @@
+//! Shorthands for common [ugli::BlendMode]s.
+
+use geng::prelude::*;
+
+/// Straight (non-premultiplied) alpha blending. The default used by `draw2d`.
+pub fn alpha() -> ugli::BlendMode {
+    ugli::BlendMode::straight_alpha()
+}
+
+/// Alpha blending for textures with premultiplied alpha.
+pub fn premultiplied() -> ugli::BlendMode {
+    ugli::BlendMode::premultiplied_alpha()
+}
+
+/// Adds the color scaled by alpha on top of the framebuffer. Useful for glows and lights.
+pub fn additive() -> ugli::BlendMode {
+    ugli::BlendMode::combined(ugli::ChannelBlendMode {
+        src_factor: ugli::BlendFactor::SrcAlpha,
+        dst_factor: ugli::BlendFactor::One,
+        equation: ugli::BlendEquation::Add,
+    })
+}
+
+/// Multiplies the framebuffer by the color. Useful for shadows and tinting.
+/// White is the neutral color, and transparent pixels leave the framebuffer unchanged.
+///
+/// The color must have premultiplied alpha, e.g. a texture loaded with
+/// [premultiply_alpha](geng::asset::TextureOptions::premultiply_alpha).
+/// With straight alpha, transparent pixels (usually black) darken the framebuffer.
+pub fn multiply() -> ugli::BlendMode {
+    // result = src * dst + dst * (1 - src_alpha)
+    // so a premultiplied color `c * a` gives `dst * lerp(1, c, a)`
+    ugli::BlendMode::combined(ugli::ChannelBlendMode {
+        src_factor: ugli::BlendFactor::DstColor,
+        dst_factor: ugli::BlendFactor::OneMinusSrcAlpha,
+        equation: ugli::BlendEquation::Add,
+    })
+}
+
+/// Evaluate the blend mode on the CPU, only supports the factors used in this module.
+#[cfg(test)]
+fn blend(mode: &ugli::BlendMode, src: Rgba<f32>, dst: Rgba<f32>) -> Rgba<f32> {
+    let channel = |mode: &ugli::ChannelBlendMode, src_value: f32, dst_value: f32| {
+        let value = |factor: ugli::BlendFactor| match factor {
+            ugli::BlendFactor::Zero => 0.0,
+            ugli::BlendFactor::One => 1.0,
+            ugli::BlendFactor::SrcAlpha => src.a,
+            ugli::BlendFactor::OneMinusSrcAlpha => 1.0 - src.a,
+            ugli::BlendFactor::DstColor => dst_value,
+            _ => unimplemented!("blend factor {factor:?}"),
+        };
+        assert!(matches!(mode.equation, ugli::BlendEquation::Add));
+        src_value * value(mode.src_factor) + dst_value * value(mode.dst_factor)
+    };
+    Rgba::new(
+        channel(&mode.rgb, src.r, dst.r),
+        channel(&mode.rgb, src.g, dst.g),
+        channel(&mode.rgb, src.b, dst.b),
+        channel(&mode.alpha, src.a, dst.a),
+    )
+}
+
+#[test]
+fn test_blend() {
+    let dst = Rgba::new(0.8, 0.4, 0.2, 1.0);
+    let clear = Rgba::new(0.0, 0.0, 0.0, 0.0);
+    let close = |a: Rgba<f32>, b: Rgba<f32>| {
+        let [a, b] = [a, b].map(|c| [c.r, c.g, c.b, c.a]);
+        a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-5)
+    };
+
+    // Transparent pixels leave the framebuffer unchanged in every mode
+    for mode in [alpha(), premultiplied(), additive(), multiply()] {
+        assert!(close(blend(&mode, clear, dst), dst));
+    }
+
+    // An opaque gray shadow halves the color
+    let shadow = Rgba::new(0.5, 0.5, 0.5, 1.0);
+    assert!(close(
+        blend(&multiply(), shadow, dst),
+        Rgba::new(0.4, 0.2, 0.1, 1.0)
+    ));
+    // A half transparent black shadow, premultiplied, also halves the color
+    let shadow = Rgba::new(0.0, 0.0, 0.0, 0.5);
+    assert!(close(
+        blend(&multiply(), shadow, dst),
+        Rgba::new(0.4, 0.2, 0.1, 1.0)
+    ));
+    // White is neutral
+    assert!(close(blend(&multiply(), Rgba::WHITE, dst), dst));
+
+    let red = Rgba::new(1.0, 0.0, 0.0, 0.5);
+    assert!(close(
+        blend(&additive(), red, dst),
+        Rgba::new(1.3, 0.4, 0.2, 1.25)
+    ));
+}
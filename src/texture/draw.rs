@@ -0,0 +1,527 @@
+use geng::prelude::*;
+
+use crate::{
+    conversions::{Aabb2RealConversions, Vec2RealConversions},
+    layout::AreaOps,
+};
+
+/// Size of the tiles when drawing a [DrawTexture] in the tiled mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileSize {
+    /// Size of a tile in world units.
+    World(vec2<f32>),
+    /// Size of a texture pixel in world units, so that tiles keep the aspect of the texture.
+    Pixels(f32),
+}
+
+/// Tiled mode of a [DrawTexture]: the texture is repeated to fill the target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tiling {
+    pub size: TileSize,
+    /// Offset of the tiles from the bottom-left corner of the target in world units.
+    pub offset: vec2<f32>,
+}
+
+impl Tiling {
+    /// Size of a single tile in world units for a texture of the given size in pixels.
+    pub fn tile_size(&self, texture_size: vec2<f32>) -> vec2<f32> {
+        match self.size {
+            TileSize::World(size) => size,
+            TileSize::Pixels(pixel_scale) => texture_size * pixel_scale,
+        }
+    }
+}
+
+/// Helper for rendering textures.
+pub struct DrawTexture<'a> {
+    /// The texture to render.
+    pub texture: &'a ugli::Texture,
+    /// World position to render at.
+    pub target: Aabb2<f32>,
+    /// Color to render the texture with.
+    pub color: Rgba<f32>,
+    /// The part of the texture to render in uv coordinates.
+    pub uv: Aabb2<f32>,
+    /// Extra transformations applied before drawing.
+    pub transform: mat3<f32>,
+    /// Whether to mirror the texture horizontally and vertically.
+    pub flip: vec2<bool>,
+    /// Rotation around the [pivot](Self::pivot), applied before the [transform](Self::transform).
+    pub rotation: Angle<f32>,
+    /// Pivot of the rotation relative to the target: (0, 0) is bottom-left, (1, 1) is top-right.
+    pub pivot: vec2<f32>,
+    /// Offset added to the uv's, used for scrolling textures.
+    pub uv_offset: vec2<f32>,
+    /// Scale of the uv's (applied before the offset).
+    pub uv_scale: vec2<f32>,
+    /// If set, the texture is repeated to fill the target instead of being stretched.
+    pub tiling: Option<Tiling>,
+    /// Parameters used for drawing: blend mode, depth, stencil, etc.
+    pub parameters: ugli::DrawParameters,
+}
+
+impl<'a> DrawTexture<'a> {
+    pub fn new(texture: &'a ugli::Texture) -> Self {
+        Self {
+            texture,
+            target: Aabb2::ZERO.extend_positive(texture.size().as_f32()),
+            color: Rgba::WHITE,
+            uv: Aabb2::ZERO.extend_positive(vec2::splat(1.0)),
+            transform: mat3::identity(),
+            flip: vec2(false, false),
+            rotation: Angle::ZERO,
+            pivot: vec2::splat(0.5),
+            uv_offset: vec2::ZERO,
+            uv_scale: vec2::splat(1.0),
+            tiling: None,
+            parameters: ugli::DrawParameters {
+                blend_mode: Some(super::blend::alpha()),
+                ..default()
+            },
+        }
+    }
+
+    pub fn colored(self, color: Rgba<f32>) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn transformed(self, transform: mat3<f32>) -> Self {
+        Self { transform, ..self }
+    }
+
+    /// Set the world position to render at.
+    pub fn with_target(self, target: Aabb2<f32>) -> Self {
+        Self { target, ..self }
+    }
+
+    /// Repeat the texture to fill the target, with tiles of the given size
+    /// offset from the bottom-left corner of the target.
+    /// The last tiles are cut off at the edges of the target.
+    pub fn tiled(self, size: TileSize, offset: vec2<f32>) -> Self {
+        Self {
+            tiling: Some(Tiling { size, offset }),
+            ..self
+        }
+    }
+
+    /// Mirror the texture horizontally.
+    pub fn flip_x(mut self) -> Self {
+        self.flip.x = !self.flip.x;
+        self
+    }
+
+    /// Mirror the texture vertically.
+    pub fn flip_y(mut self) -> Self {
+        self.flip.y = !self.flip.y;
+        self
+    }
+
+    /// Rotate around the center of the target.
+    pub fn rotated(self, rotation: Angle<f32>) -> Self {
+        self.rotated_around(rotation, vec2::splat(0.5))
+    }
+
+    /// Rotate around the `pivot` given relative to the texture:
+    /// (0, 0) is the bottom-left corner, (1, 1) is the top-right corner.
+    /// The pivot is mirrored together with the texture when flipped.
+    pub fn rotated_around(self, rotation: Angle<f32>, pivot: vec2<f32>) -> Self {
+        Self {
+            rotation,
+            pivot,
+            ..self
+        }
+    }
+
+    /// Offset the uv's by `offset` and scale them by `scale`.
+    /// Use a texture with [ugli::WrapMode::Repeat] to scroll it continuously,
+    /// e.g. by increasing the offset with time.
+    pub fn uv_scrolled(self, offset: vec2<f32>, scale: vec2<f32>) -> Self {
+        Self {
+            uv_offset: offset,
+            uv_scale: scale,
+            ..self
+        }
+    }
+
+    /// Render only a part of the texture given in uv coordinates.
+    /// The target is resized to the size of the sub-texture in pixels,
+    /// so this should be called before any of the fitting methods.
+    pub fn sub_texture(self, uv: Aabb2<f32>) -> Self {
+        let this = Self { uv, ..self };
+        let target = Aabb2::point(this.target.min).extend_positive(this.size());
+        Self { target, ..this }
+    }
+
+    /// Render only a part of the texture given in pixel coordinates,
+    /// with the origin in the bottom-left corner, same as uv.
+    /// The target is resized to the size of the rect,
+    /// so this should be called before any of the fitting methods.
+    pub fn source_rect(self, rect: Aabb2<usize>) -> Self {
+        let uv = pixel_rect_uv(rect, self.texture.size());
+        self.sub_texture(uv)
+    }
+
+    /// Render a single cell of a sprite sheet evenly divided into `grid` cells.
+    /// Cells are indexed row by row starting from the top-left corner.
+    ///
+    /// # Panics
+    /// Panics if the `index` is outside of the grid.
+    pub fn sprite_sheet(self, grid: vec2<usize>, index: usize) -> Self {
+        assert!(
+            index < grid.x * grid.y,
+            "sprite index is outside of the grid"
+        );
+        self.sub_texture(sprite_sheet_uv(grid, index))
+    }
+
+    /// Render a texture of an image trimmed by [crate::image_ops::trim],
+    /// positioned as the original untrimmed image would be at the `target`.
+    pub fn trimmed(self, trim: &crate::image_ops::Trim, target: Aabb2<f32>) -> Self {
+        let target = trim.target(target);
+        Self { target, ..self }
+    }
+
+    /// Size of the rendered part of the texture in pixels.
+    pub fn size(&self) -> vec2<f32> {
+        self.texture.size().as_f32() * self.uv.size()
+    }
+
+    /// Fit into the target [Aabb2] in world space and align.
+    pub fn fit(self, target: Aabb2<f32>, align: vec2<f32>) -> Self {
+        let target = target.fit_aabb(self.size(), align);
+        Self { target, ..self }
+    }
+
+    /// Fit into the target [Aabb2] by width in world space and align vertically.
+    pub fn fit_width(self, target: Aabb2<f32>, align: f32) -> Self {
+        let target = target.fit_aabb_width(self.size(), align);
+        Self { target, ..self }
+    }
+
+    /// Fit into the target [Aabb2] by height in world space and align horizontally.
+    pub fn fit_height(self, target: Aabb2<f32>, align: f32) -> Self {
+        let target = target.fit_aabb_height(self.size(), align);
+        Self { target, ..self }
+    }
+
+    /// Fit to the whole framebuffer.
+    pub fn fit_screen(self, align: vec2<f32>, framebuffer: &ugli::Framebuffer) -> Self {
+        self.fit(
+            Aabb2::ZERO.extend_positive(framebuffer.size().as_f32()),
+            align,
+        )
+    }
+
+    /// Fit to the width of the framebuffer.
+    pub fn fit_screen_width(self, align: f32, framebuffer: &mut ugli::Framebuffer) -> Self {
+        self.fit_width(
+            Aabb2::ZERO.extend_positive(framebuffer.size().as_f32()),
+            align,
+        )
+    }
+
+    /// Fit to the height of the framebuffer.
+    pub fn fit_screen_height(self, align: f32, framebuffer: &mut ugli::Framebuffer) -> Self {
+        self.fit_height(
+            Aabb2::ZERO.extend_positive(framebuffer.size().as_f32()),
+            align,
+        )
+    }
+
+    /// Specify the [DrawParameters](ugli::DrawParameters) to use when rendering.
+    pub fn with_parameters(self, parameters: ugli::DrawParameters) -> Self {
+        Self { parameters, ..self }
+    }
+
+    /// Specify the blend mode. `None` disables blending.
+    pub fn blended(mut self, blend_mode: Option<ugli::BlendMode>) -> Self {
+        self.parameters.blend_mode = blend_mode;
+        self
+    }
+
+    /// Use straight alpha blending (the default).
+    pub fn blend_alpha(self) -> Self {
+        self.blended(Some(super::blend::alpha()))
+    }
+
+    /// Use alpha blending for a texture with premultiplied alpha.
+    pub fn blend_premultiplied(self) -> Self {
+        self.blended(Some(super::blend::premultiplied()))
+    }
+
+    /// Use additive blending.
+    pub fn blend_additive(self) -> Self {
+        self.blended(Some(super::blend::additive()))
+    }
+
+    /// Use multiplicative blending, e.g. for shadows.
+    /// The texture must have premultiplied alpha, see [super::blend::multiply].
+    pub fn blend_multiply(self) -> Self {
+        self.blended(Some(super::blend::multiply()))
+    }
+
+    /// Specify the depth test and whether to write into the depth buffer.
+    pub fn depth(mut self, depth_func: Option<ugli::DepthFunc>, write_depth: bool) -> Self {
+        self.parameters.depth_func = depth_func;
+        self.parameters.write_depth = write_depth;
+        self
+    }
+
+    /// Specify the stencil test and operations.
+    pub fn stencil(mut self, stencil_mode: Option<ugli::StencilMode>) -> Self {
+        self.parameters.stencil_mode = stencil_mode;
+        self
+    }
+
+    /// Align to pixel-perfect: such that pixels of the texture align with the pixels of the framebuffer.
+    pub fn pixel_perfect(
+        self,
+        pos: vec2<f32>,
+        align: vec2<f32>,
+        pixel_scale: f32,
+        camera: &impl geng::AbstractCamera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) -> Self {
+        let size = (self.size() * pixel_scale).map(|x| x.round() as usize);
+        let target =
+            crate::pixel::pixel_perfect_aabb(pos, align, size, camera, framebuffer.size().as_f32());
+        Self { target, ..self }
+    }
+
+    /// The full transformation of the unit quad.
+    pub fn quad_transform(&self) -> mat3<f32> {
+        unit_quad_transform(
+            self.target,
+            self.pivot,
+            self.flip,
+            self.rotation,
+            self.transform,
+        )
+    }
+
+    /// Vertices of the unit quad with uv's of the sub-texture, flipped and scrolled.
+    /// The quad has to be transformed by [Self::quad_transform] to get to the world space.
+    /// Ignores the [tiling](Self::tiling), see [Self::triangles].
+    pub fn vertices(&self) -> [draw2d::TexturedVertex; 4] {
+        let mut quad = crate::geometry::unit_quad();
+        for (vertex, uv) in quad.iter_mut().zip(self.flipped_uv().corners()) {
+            vertex.a_vt = self.scroll_uv(uv);
+        }
+        quad
+    }
+
+    /// Vertices as a list of triangles, tiled if [tiling](Self::tiling) is set.
+    /// Same as [Self::vertices], the triangles have to be transformed by [Self::quad_transform].
+    pub fn triangles(&self) -> Vec<draw2d::TexturedVertex> {
+        let Some(tiling) = self.tiling else {
+            let quad = self.vertices();
+            return [0, 1, 2, 0, 2, 3].map(|i| quad[i]).to_vec();
+        };
+
+        let tile_size = tiling.tile_size(self.size());
+        crate::tiled::tile_unit_quad(
+            self.flipped_uv(),
+            tile_size,
+            tiling.offset,
+            self.target.size(),
+        )
+        .into_iter()
+        .map(|vertex| draw2d::TexturedVertex {
+            a_vt: self.scroll_uv(vertex.a_vt),
+            ..vertex
+        })
+        .collect()
+    }
+
+    fn flipped_uv(&self) -> Aabb2<f32> {
+        flip_uv(self.uv, self.flip)
+    }
+
+    fn scroll_uv(&self, uv: vec2<f32>) -> vec2<f32> {
+        scroll_uv(uv, self.uv_offset, self.uv_scale)
+    }
+
+    /// Vertices as a list of triangles in world space, with the [color](Self::color) applied.
+    pub fn world_triangles(&self) -> Vec<draw2d::TexturedVertex> {
+        let transform = self.quad_transform();
+        self.triangles()
+            .into_iter()
+            .map(|vertex| super::batch::transform_vertex(vertex, transform, self.color))
+            .collect()
+    }
+
+    /// Convert into a polygon that can be drawn via `draw2d`.
+    /// Note that `draw2d` ignores the [parameters](Self::parameters).
+    pub fn as_textured_polygon(self) -> impl draw2d::Draw2d + 'a {
+        draw2d::TexturedPolygon::with_mode(
+            self.world_triangles(),
+            self.texture,
+            ugli::DrawMode::Triangles,
+        )
+    }
+
+    /// Convert into a quad that can be drawn via `draw2d`.
+    /// The whole texture is drawn, ignoring the uv's, flips, scrolling and tiling.
+    #[deprecated(note = "ignores the uv's, use `as_textured_polygon` instead")]
+    pub fn as_textured_quad(self) -> draw2d::TexturedQuad<&'a ugli::Texture> {
+        let transform = self.quad_transform();
+        draw2d::TexturedQuad::unit_colored(self.texture, self.color).transform(transform)
+    }
+
+    pub fn draw(
+        self,
+        camera: &impl geng::AbstractCamera2d,
+        geng: &Geng,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        super::program::draw_textured(
+            &self.triangles(),
+            ugli::DrawMode::Triangles,
+            self.texture,
+            self.color,
+            self.quad_transform(),
+            &self.parameters,
+            camera,
+            geng,
+            framebuffer,
+        );
+    }
+}
+
+/// Transformation of the unit quad into the `target`, rotated around the `pivot` relative
+/// to the target, which is mirrored together with the texture when flipped.
+/// The rotation is applied before the extra `transform`.
+fn unit_quad_transform(
+    target: Aabb2<f32>,
+    pivot: vec2<f32>,
+    flip: vec2<bool>,
+    rotation: Angle<f32>,
+    transform: mat3<f32>,
+) -> mat3<f32> {
+    let mut pivot = pivot;
+    if flip.x {
+        pivot.x = 1.0 - pivot.x;
+    }
+    if flip.y {
+        pivot.y = 1.0 - pivot.y;
+    }
+    let pivot = (pivot - vec2::splat(0.5)) * target.size();
+    let rotation = mat3::translate(pivot) * mat3::rotate(rotation) * mat3::translate(-pivot);
+
+    mat3::translate(target.center()) * transform * rotation * mat3::scale(target.size() / 2.0)
+}
+
+/// Mirror the uv's horizontally and vertically.
+fn flip_uv(mut uv: Aabb2<f32>, flip: vec2<bool>) -> Aabb2<f32> {
+    if flip.x {
+        std::mem::swap(&mut uv.min.x, &mut uv.max.x);
+    }
+    if flip.y {
+        std::mem::swap(&mut uv.min.y, &mut uv.max.y);
+    }
+    uv
+}
+
+/// Scale the uv, then offset it.
+fn scroll_uv(uv: vec2<f32>, offset: vec2<f32>, scale: vec2<f32>) -> vec2<f32> {
+    uv * scale + offset
+}
+
+/// Uv's of a pixel rect in a texture of the given size.
+/// Both use texture coordinates with the origin in the bottom-left corner.
+fn pixel_rect_uv(rect: Aabb2<usize>, texture_size: vec2<usize>) -> Aabb2<f32> {
+    let size = texture_size.as_f32();
+    rect.as_f32().map_bounds(|pos| pos / size)
+}
+
+/// Uv's of a cell of a sprite sheet, with cells indexed row by row from the top-left.
+/// The first row of an image is uploaded as the top row of the texture
+/// (the one with the largest v), see [super::texture_from_image].
+fn sprite_sheet_uv(grid: vec2<usize>, index: usize) -> Aabb2<f32> {
+    let cell = vec2(index % grid.x, grid.y - 1 - index / grid.x);
+    let size = vec2::splat(1.0) / grid.as_f32();
+    Aabb2::point(cell.as_f32() * size).extend_positive(size)
+}
+
+#[test]
+fn test_sprite_sheet_uv() {
+    let grid = vec2(3, 2);
+    let third = 1.0 / 3.0;
+    let uv = |min: vec2<f32>| Aabb2::point(min).extend_positive(vec2(third, 0.5));
+
+    // Index 0 is the top-left cell, the top row has the larger v
+    assert_eq!(sprite_sheet_uv(grid, 0), uv(vec2(0.0, 0.5)));
+    assert_eq!(sprite_sheet_uv(grid, 2), uv(vec2(2.0 * third, 0.5)));
+    assert_eq!(sprite_sheet_uv(grid, 3), uv(vec2(0.0, 0.0)));
+    assert_eq!(sprite_sheet_uv(grid, 5), uv(vec2(2.0 * third, 0.0)));
+
+    // Cells of 16x16 pixels, the pixel rect has the origin in the bottom-left corner
+    let texture_size = vec2(48, 32);
+    let rect = Aabb2::point(vec2(16, 0)).extend_positive(vec2(16, 16));
+    assert_eq!(pixel_rect_uv(rect, texture_size), sprite_sheet_uv(grid, 4));
+
+    // Same as the region of an image placed into the top-left corner of an atlas image
+    let mut atlas = image::RgbaImage::new(48, 32);
+    let cell = image::RgbaImage::new(16, 16);
+    let region = crate::atlas::place(&mut atlas, &cell, vec2(0, 0), 0);
+    assert_eq!(region.uv, sprite_sheet_uv(grid, 0));
+    assert_eq!(pixel_rect_uv(region.pixels, texture_size), region.uv);
+}
+
+#[test]
+fn test_unit_quad_transform() {
+    let apply = |matrix: mat3<f32>, pos: vec2<f32>| {
+        let pos = matrix * pos.extend(1.0);
+        pos.xy() / pos.z
+    };
+    let close = |a: vec2<f32>, b: vec2<f32>| (a - b).len() < 1e-4;
+    let quarter = Angle::from_degrees(90.0);
+    let target = Aabb2::point(vec2(10.0, 0.0)).extend_positive(vec2(4.0, 2.0));
+
+    let matrix = unit_quad_transform(
+        target,
+        vec2::ZERO,
+        vec2(false, false),
+        Angle::ZERO,
+        mat3::identity(),
+    );
+    assert!(close(apply(matrix, vec2(-1.0, -1.0)), target.min));
+    assert!(close(apply(matrix, vec2(1.0, 1.0)), target.max));
+
+    // The bottom-left pivot is mirrored to the bottom-right corner and stays in place
+    let matrix = unit_quad_transform(
+        target,
+        vec2::ZERO,
+        vec2(true, false),
+        quarter,
+        mat3::identity(),
+    );
+    assert!(close(apply(matrix, vec2(1.0, -1.0)), vec2(14.0, 0.0)));
+    // The top-left corner rotates counter-clockwise around the pivot
+    assert!(close(apply(matrix, vec2(-1.0, 1.0)), vec2(12.0, -4.0)));
+
+    // Rotation is applied before the transform
+    let matrix = unit_quad_transform(
+        target,
+        vec2::splat(0.5),
+        vec2(false, false),
+        quarter,
+        mat3::scale(vec2(2.0, 1.0)),
+    );
+    assert!(close(apply(matrix, vec2(1.0, 1.0)), vec2(10.0, 3.0)));
+}
+
+#[test]
+fn test_flip_scroll_uv() {
+    let uv = Aabb2::ZERO.extend_positive(vec2(0.5, 1.0));
+    let flipped = flip_uv(uv, vec2(true, false));
+    assert_eq!(flipped.min, vec2(0.5, 0.0));
+    assert_eq!(flipped.max, vec2(0.0, 1.0));
+    assert_eq!(flip_uv(flipped, vec2(true, false)), uv);
+
+    // Scale is applied before the offset
+    let offset = vec2(0.25, -1.0);
+    let scale = vec2(2.0, 3.0);
+    assert_eq!(scroll_uv(flipped.min, offset, scale), vec2(1.25, -1.0));
+    assert_eq!(scroll_uv(flipped.max, offset, scale), vec2(0.25, 2.0));
+}
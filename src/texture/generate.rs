@@ -0,0 +1,233 @@
+//! Procedural images, useful as placeholder textures.
+//!
+//! All generators take positions in texture coordinates, with the origin in the bottom-left corner,
+//! so the result looks the same after uploading with [super::texture_from_image].
+
+use geng::prelude::*;
+
+use crate::conversions::AngleDirectionConversions;
+
+/// Generate an image by evaluating the color at the center of every pixel.
+/// The position is in pixels, with the origin in the bottom-left corner.
+pub fn from_fn(size: vec2<usize>, f: impl Fn(vec2<f32>) -> Rgba<f32>) -> image::RgbaImage {
+    let (width, height) = (size.x as u32, size.y as u32);
+    image::RgbaImage::from_fn(width, height, |x, y| {
+        let pos = vec2(x as f32, (height - 1 - y) as f32) + vec2::splat(0.5);
+        let color = crate::color::rgba_to_u8(f(pos));
+        image::Rgba([color.r, color.g, color.b, color.a])
+    })
+}
+
+/// A checkerboard of square cells `cell_size` pixels wide,
+/// starting with color `a` in the bottom-left corner.
+pub fn checkerboard(
+    size: vec2<usize>,
+    cell_size: usize,
+    a: Rgba<f32>,
+    b: Rgba<f32>,
+) -> image::RgbaImage {
+    let cell_size = cell_size.max(1) as f32;
+    from_fn(size, |pos| {
+        let cell = (pos / cell_size).map(|x| x.floor() as i64);
+        if (cell.x + cell.y) % 2 == 0 { a } else { b }
+    })
+}
+
+/// A linear gradient from color `from` to color `to` in the direction of the `angle`.
+/// An angle of zero goes from left to right.
+pub fn linear_gradient(
+    size: vec2<usize>,
+    from: Rgba<f32>,
+    to: Rgba<f32>,
+    angle: Angle<f32>,
+) -> image::RgbaImage {
+    let half = size.map(|x| x as f32) / 2.0;
+    let dir = angle.to_direction();
+    // Projection of the corners onto the direction
+    let extent = dir.x.abs() * half.x + dir.y.abs() * half.y;
+    from_fn(size, |pos| {
+        let t = if extent > 0.0 {
+            (vec2::dot(pos - half, dir) / extent + 1.0) / 2.0
+        } else {
+            0.0
+        };
+        lerp_color(from, to, t)
+    })
+}
+
+/// A radial gradient from color `inner` in the center to color `outer`
+/// at the distance of half of the smallest side and farther.
+pub fn radial_gradient(size: vec2<usize>, inner: Rgba<f32>, outer: Rgba<f32>) -> image::RgbaImage {
+    let half = size.map(|x| x as f32) / 2.0;
+    let radius = half.x.min(half.y);
+    from_fn(size, |pos| {
+        let t = if radius > 0.0 {
+            (pos - half).len() / radius
+        } else {
+            1.0
+        };
+        lerp_color(inner, outer, t)
+    })
+}
+
+/// Grayscale [value noise](value_noise_2d) with lattice cells `scale` pixels wide.
+pub fn value_noise(size: vec2<usize>, scale: f32, seed: u64) -> image::RgbaImage {
+    from_fn(size, |pos| gray(value_noise_2d(pos / scale, seed)))
+}
+
+/// Grayscale [Perlin noise](perlin_noise_2d) with lattice cells `scale` pixels wide.
+pub fn perlin_noise(size: vec2<usize>, scale: f32, seed: u64) -> image::RgbaImage {
+    from_fn(size, |pos| gray(perlin_noise_2d(pos / scale, seed)))
+}
+
+/// A filled circle inscribed into the image, with anti-aliased edges.
+pub fn circle(size: vec2<usize>, color: Rgba<f32>) -> image::RgbaImage {
+    let half = size.map(|x| x as f32) / 2.0;
+    let radius = half.x.min(half.y);
+    from_fn(size, |pos| {
+        let distance = (pos - half).len() - radius;
+        with_coverage(color, distance)
+    })
+}
+
+/// A filled rect covering the image, with corners rounded by `radius` pixels
+/// and anti-aliased edges.
+pub fn rounded_rect(size: vec2<usize>, radius: f32, color: Rgba<f32>) -> image::RgbaImage {
+    let half = size.map(|x| x as f32) / 2.0;
+    let radius = radius.clamp(0.0, half.x.min(half.y));
+    from_fn(size, |pos| {
+        // Signed distance to a rounded box
+        let delta = pos - half;
+        let q = vec2(delta.x.abs(), delta.y.abs()) - (half - vec2::splat(radius));
+        let outside = vec2(q.x.max(0.0), q.y.max(0.0)).len();
+        let inside = q.x.max(q.y).min(0.0);
+        with_coverage(color, outside + inside - radius)
+    })
+}
+
+/// Smooth noise interpolated between random values at integer lattice points.
+/// Returns a value in range `0.0..=1.0`.
+pub fn value_noise_2d(pos: vec2<f32>, seed: u64) -> f32 {
+    let cell = pos.map(|x| x.floor());
+    let t = (pos - cell).map(smoothstep);
+    let cell = cell.map(|x| x as i64);
+    let value = |dx: i64, dy: i64| unit_hash(cell.x + dx, cell.y + dy, seed);
+
+    let bottom = lerp(value(0, 0), value(1, 0), t.x);
+    let top = lerp(value(0, 1), value(1, 1), t.x);
+    lerp(bottom, top, t.y)
+}
+
+/// Classic Perlin gradient noise with random gradients at integer lattice points.
+/// Returns a value in range `0.0..=1.0`, with 0.5 at the lattice points.
+pub fn perlin_noise_2d(pos: vec2<f32>, seed: u64) -> f32 {
+    let cell = pos.map(|x| x.floor());
+    let offset = pos - cell;
+    let t = offset.map(fade);
+    let cell = cell.map(|x| x as i64);
+    let dot = |dx: i64, dy: i64| {
+        let angle =
+            Angle::from_radians(unit_hash(cell.x + dx, cell.y + dy, seed) * std::f32::consts::TAU);
+        vec2::dot(angle.to_direction(), offset - vec2(dx as f32, dy as f32))
+    };
+
+    let bottom = lerp(dot(0, 0), dot(1, 0), t.x);
+    let top = lerp(dot(0, 1), dot(1, 1), t.x);
+    // The range of 2d Perlin noise is [-sqrt(0.5), sqrt(0.5)]
+    let value = lerp(bottom, top, t.y) * std::f32::consts::SQRT_2;
+    ((value + 1.0) / 2.0).clamp(0.0, 1.0)
+}
+
+/// A pseudo-random value in range `0.0..1.0` for the lattice point.
+fn unit_hash(x: i64, y: i64, seed: u64) -> f32 {
+    // SplitMix64 finalizer over the combined coordinates
+    let mut h = seed
+        ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    h = (h ^ (h >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    h ^= h >> 31;
+    (h >> 40) as f32 / (1u64 << 24) as f32
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn lerp_color(a: Rgba<f32>, b: Rgba<f32>, t: f32) -> Rgba<f32> {
+    let t = t.clamp(0.0, 1.0);
+    Rgba::new(
+        lerp(a.r, b.r, t),
+        lerp(a.g, b.g, t),
+        lerp(a.b, b.b, t),
+        lerp(a.a, b.a, t),
+    )
+}
+
+fn gray(value: f32) -> Rgba<f32> {
+    Rgba::new(value, value, value, 1.0)
+}
+
+/// Scale the alpha by the pixel coverage given the signed distance to the shape's edge in pixels.
+fn with_coverage(color: Rgba<f32>, distance: f32) -> Rgba<f32> {
+    let coverage = (0.5 - distance).clamp(0.0, 1.0);
+    Rgba {
+        a: color.a * coverage,
+        ..color
+    }
+}
+
+#[test]
+fn test_generate() {
+    let pixel = |image: &image::RgbaImage, x: u32, y: u32| image.get_pixel(x, y).0;
+    let (white, black) = (Rgba::WHITE, Rgba::BLACK);
+
+    // Image rows are top-down, so the bottom-left cell is in the last row
+    let image = checkerboard(vec2(4, 4), 2, white, black);
+    assert_eq!(pixel(&image, 0, 3), [255; 4]);
+    assert_eq!(pixel(&image, 2, 3), [0, 0, 0, 255]);
+    assert_eq!(pixel(&image, 3, 0), [255; 4]);
+
+    let image = linear_gradient(vec2(4, 1), black, white, Angle::ZERO);
+    let values: Vec<u8> = (0..4).map(|x| pixel(&image, x, 0)[0]).collect();
+    assert!(values.windows(2).all(|w| w[0] < w[1]), "{values:?}");
+    let image = linear_gradient(vec2(1, 4), black, white, Angle::from_degrees(90.0));
+    assert!(pixel(&image, 0, 0)[0] > pixel(&image, 0, 3)[0]);
+
+    let image = radial_gradient(vec2(8, 8), white, black);
+    assert!(pixel(&image, 4, 4)[0] > 200);
+    assert_eq!(pixel(&image, 0, 0)[0], 0);
+
+    let noises: [fn(vec2<f32>, u64) -> f32; 2] = [value_noise_2d, perlin_noise_2d];
+    for noise in noises {
+        for i in 0..100 {
+            let pos = vec2(i as f32 * 0.37, i as f32 * 0.71 - 10.0);
+            let value = noise(pos, 42);
+            assert!((0.0..=1.0).contains(&value));
+            assert_eq!(value, noise(pos, 42));
+        }
+    }
+    assert_ne!(
+        value_noise(vec2(8, 8), 4.0, 1),
+        value_noise(vec2(8, 8), 4.0, 2)
+    );
+    assert!((perlin_noise_2d(vec2(3.0, -2.0), 7) - 0.5).abs() < 1e-5);
+
+    let image = circle(vec2(9, 9), white);
+    assert_eq!(pixel(&image, 4, 4)[3], 255);
+    assert_eq!(pixel(&image, 0, 0)[3], 0);
+    // Anti-aliased edge
+    assert!((1..255).contains(&pixel(&image, 1, 1)[3]));
+
+    let image = rounded_rect(vec2(10, 10), 4.0, white);
+    assert_eq!(pixel(&image, 0, 5)[3], 255);
+    assert_eq!(pixel(&image, 0, 0)[3], 0);
+}
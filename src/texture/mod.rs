@@ -0,0 +1,161 @@
+mod batch;
+pub mod blend;
+mod draw;
+pub mod generate;
+mod nine_slice;
+mod program;
+mod readback;
+mod target;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::readback::save_screenshot;
+pub use self::{
+    batch::{SpriteBatch, SpriteBatchPart, transform_quad},
+    draw::{DrawTexture, TileSize, Tiling},
+    nine_slice::{DrawNineSlice, NineSliceBorders, SliceFill},
+    readback::{
+        encode_png, image_from_bottom_up, read_framebuffer, read_texture, timestamped_name,
+    },
+    target::{DepthFormat, RenderTarget, RenderTargetPool},
+};
+
+use geng::prelude::*;
+
+/// Options for textures used as render targets.
+#[derive(Debug, Clone, Copy)]
+pub struct RenderTargetOptions {
+    pub filter: ugli::Filter,
+    pub wrap_mode: ugli::WrapMode,
+    /// Color to fill the new texture with.
+    pub clear_color: Rgba<f32>,
+    /// Whether to copy the old contents into the texture when resizing.
+    /// The contents stay anchored to the bottom-left corner.
+    pub preserve_contents: bool,
+}
+
+impl Default for RenderTargetOptions {
+    fn default() -> Self {
+        Self {
+            filter: ugli::Filter::Nearest,
+            wrap_mode: ugli::WrapMode::Clamp,
+            clear_color: Rgba::BLACK,
+            preserve_contents: false,
+        }
+    }
+}
+
+impl RenderTargetOptions {
+    /// Apply the filter and the wrap mode to the texture.
+    pub fn apply(&self, texture: &mut ugli::Texture) {
+        texture.set_filter(self.filter);
+        texture.set_wrap_mode(self.wrap_mode);
+    }
+}
+
+/// Upload the image into a new texture, applying the options same as when loading assets.
+/// The first row of the image becomes the top row of the texture.
+pub fn texture_from_image(
+    ugli: &Ugli,
+    mut image: image::RgbaImage,
+    options: &geng::asset::TextureOptions,
+) -> ugli::Texture {
+    if options.premultiply_alpha {
+        for pixel in image.pixels_mut() {
+            use image::Pixel;
+            *pixel = pixel
+                .map_without_alpha(|x| (x as f32 * (pixel[3] as f32 / 0xff as f32)).round() as u8);
+        }
+    }
+
+    let mut texture = ugli::Texture::from_image_image(ugli, image);
+    texture.set_filter(options.filter);
+    texture.set_wrap_mode(options.wrap_mode);
+    texture
+}
+
+/// Construct a new black texture of the given size with the default [RenderTargetOptions].
+pub fn new_texture(ugli: &Ugli, size: vec2<usize>) -> ugli::Texture {
+    new_texture_with(ugli, size, &RenderTargetOptions::default())
+}
+
+/// Construct a new texture of the given size filled with the clear color.
+pub fn new_texture_with(
+    ugli: &Ugli,
+    size: vec2<usize>,
+    options: &RenderTargetOptions,
+) -> ugli::Texture {
+    let mut texture = ugli::Texture::new_with(ugli, size, |_| options.clear_color);
+    options.apply(&mut texture);
+    texture
+}
+
+/// Ensure the texture's size is equal to `size` using the default [RenderTargetOptions].
+/// If the size is the same, the texture doesn't change.
+pub fn update_texture_size(texture: &mut ugli::Texture, size: vec2<usize>, ugli: &Ugli) {
+    if texture.size() != size {
+        *texture = new_texture(ugli, size);
+    }
+}
+
+/// Ensure the texture's size is equal to `size`.
+/// If the size is the same, the texture doesn't change.
+/// If the options [preserve the contents](RenderTargetOptions::preserve_contents),
+/// the old texture is drawn into the new one on the GPU.
+pub fn update_texture_size_with(
+    texture: &mut ugli::Texture,
+    size: vec2<usize>,
+    options: &RenderTargetOptions,
+    geng: &Geng,
+) {
+    if texture.size() == size {
+        return;
+    }
+
+    let mut new = new_texture_with(geng.ugli(), size, options);
+    if options.preserve_contents {
+        let mut framebuffer = attach_texture(&mut new, geng.ugli());
+        DrawTexture::new(texture)
+            .with_target(preserved_target(texture.size()))
+            .blended(None)
+            .draw(&geng::PixelPerfectCamera, geng, &mut framebuffer);
+    }
+    *texture = new;
+}
+
+/// Where to draw the old contents of a resized texture with a [geng::PixelPerfectCamera],
+/// keeping them at their pixel size anchored to the bottom-left corner.
+fn preserved_target(old_size: vec2<usize>) -> Aabb2<f32> {
+    Aabb2::ZERO.extend_positive(old_size.map(|x| x as f32))
+}
+
+/// Attach a texture to a color-only framebuffer.
+pub fn attach_texture<'a>(texture: &'a mut ugli::Texture, ugli: &Ugli) -> ugli::Framebuffer<'a> {
+    ugli::Framebuffer::new_color(ugli, ugli::ColorAttachment::Texture(texture))
+}
+
+#[test]
+fn test_preserved_target() {
+    use geng::AbstractCamera2d;
+
+    // Clip space position of a pixel position in the new texture
+    let clip = |pos: vec2<f32>, new_size: vec2<f32>| {
+        let camera = geng::PixelPerfectCamera;
+        let matrix = camera.projection_matrix(new_size) * camera.view_matrix();
+        let pos = matrix * pos.extend(1.0);
+        pos.xy() / pos.z
+    };
+
+    let old_size = vec2(4, 2);
+    let target = preserved_target(old_size);
+    assert_eq!(target, Aabb2::ZERO.extend_positive(vec2(4.0, 2.0)));
+
+    // Growing: the old contents cover the bottom-left part of the new texture
+    let new_size = vec2(8.0, 8.0);
+    assert_eq!(clip(target.min, new_size), vec2(-1.0, -1.0));
+    assert_eq!(clip(target.max, new_size), vec2(0.0, -0.5));
+
+    // Shrinking: the top and right parts of the old contents are cut off
+    let new_size = vec2(2.0, 1.0);
+    assert_eq!(clip(target.min, new_size), vec2(-1.0, -1.0));
+    assert_eq!(clip(target.max, new_size), vec2(3.0, 3.0));
+}
@@ -0,0 +1,244 @@
+use geng::prelude::*;
+
+use crate::conversions::Vec2RealConversions;
+
+/// Sizes of the borders of a nine-slice texture in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NineSliceBorders {
+    pub left: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub top: usize,
+}
+
+impl NineSliceBorders {
+    /// The same border size on every side.
+    pub fn uniform(size: usize) -> Self {
+        Self {
+            left: size,
+            right: size,
+            bottom: size,
+            top: size,
+        }
+    }
+}
+
+/// How to fill the area of a slice with its part of the texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliceFill {
+    /// Stretch the texture over the whole area.
+    Stretch,
+    /// Repeat the texture, cutting off the last tile.
+    Tile,
+}
+
+/// Helper for rendering textures as [nine-slices](https://en.wikipedia.org/wiki/9-slice_scaling):
+/// corners keep their size, while edges and center are stretched or tiled.
+#[derive(Debug, Clone, Copy)]
+pub struct DrawNineSlice {
+    /// Borders of the texture in pixels.
+    pub borders: NineSliceBorders,
+    /// World position to render at.
+    pub target: Aabb2<f32>,
+    /// Size of a texture pixel in world units.
+    /// If the borders do not fit into the target, they are shrunk.
+    pub pixel_scale: f32,
+    /// How to fill the center.
+    pub center: SliceFill,
+    /// How to fill the edges.
+    pub edges: SliceFill,
+    /// Color to render the texture with.
+    pub color: Rgba<f32>,
+}
+
+impl DrawNineSlice {
+    pub fn new(borders: NineSliceBorders, target: Aabb2<f32>) -> Self {
+        Self {
+            borders,
+            target,
+            pixel_scale: 1.0,
+            center: SliceFill::Stretch,
+            edges: SliceFill::Stretch,
+            color: Rgba::WHITE,
+        }
+    }
+
+    pub fn colored(self, color: Rgba<f32>) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn scaled(self, pixel_scale: f32) -> Self {
+        Self {
+            pixel_scale,
+            ..self
+        }
+    }
+
+    pub fn fill_center(self, center: SliceFill) -> Self {
+        Self { center, ..self }
+    }
+
+    pub fn fill_edges(self, edges: SliceFill) -> Self {
+        Self { edges, ..self }
+    }
+
+    /// Generate the geometry for a texture of the given size as a list of triangles.
+    pub fn vertices(&self, texture_size: vec2<usize>) -> Vec<draw2d::TexturedVertex> {
+        let texture_size = texture_size.as_f32();
+        let borders = self.borders;
+        let target = self.target;
+
+        // Borders in world space, shrunk if they do not fit
+        let world_borders = |low: usize, high: usize, available: f32| {
+            let low = low as f32 * self.pixel_scale;
+            let high = high as f32 * self.pixel_scale;
+            let total = low + high;
+            let scale = if total > available && total > 0.0 {
+                available.max(0.0) / total
+            } else {
+                1.0
+            };
+            (low * scale, high * scale)
+        };
+        let (left, right) = world_borders(borders.left, borders.right, target.width());
+        let (bottom, top) = world_borders(borders.bottom, borders.top, target.height());
+
+        let xs = [
+            target.min.x,
+            target.min.x + left,
+            target.max.x - right,
+            target.max.x,
+        ];
+        let ys = [
+            target.min.y,
+            target.min.y + bottom,
+            target.max.y - top,
+            target.max.y,
+        ];
+        let us = [
+            0.0,
+            borders.left as f32 / texture_size.x,
+            1.0 - borders.right as f32 / texture_size.x,
+            1.0,
+        ];
+        let vs = [
+            0.0,
+            borders.bottom as f32 / texture_size.y,
+            1.0 - borders.top as f32 / texture_size.y,
+            1.0,
+        ];
+
+        let mut vertices = Vec::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                let area = Aabb2 {
+                    min: vec2(xs[x], ys[y]),
+                    max: vec2(xs[x + 1], ys[y + 1]),
+                };
+                if area.width() <= 0.0 || area.height() <= 0.0 {
+                    continue;
+                }
+                let uv = Aabb2 {
+                    min: vec2(us[x], vs[y]),
+                    max: vec2(us[x + 1], vs[y + 1]),
+                };
+
+                let fill = match (x, y) {
+                    (1, 1) => self.center,
+                    (1, _) | (_, 1) => self.edges,
+                    _ => SliceFill::Stretch,
+                };
+                match fill {
+                    SliceFill::Stretch => vertices.extend(quad(area, uv)),
+                    SliceFill::Tile => {
+                        let mut tile_size = uv.size() * texture_size * self.pixel_scale;
+                        // Edges are only tiled along their length
+                        if x != 1 {
+                            tile_size.x = area.width();
+                        }
+                        if y != 1 {
+                            tile_size.y = area.height();
+                        }
+                        if tile_size.x <= 0.0 || tile_size.y <= 0.0 {
+                            continue;
+                        }
+                        let tiles =
+                            crate::tiled::tile_area_subtexture(uv, tile_size, vec2::ZERO, area);
+                        vertices.extend(crate::tiled::clip_tiles(&tiles, area));
+                    }
+                }
+            }
+        }
+
+        for vertex in &mut vertices {
+            vertex.a_color = self.color;
+        }
+        vertices
+    }
+
+    pub fn draw(
+        &self,
+        texture: &ugli::Texture,
+        camera: &impl geng::AbstractCamera2d,
+        geng: &Geng,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let vertices = self.vertices(texture.size());
+        let polygon =
+            draw2d::TexturedPolygon::with_mode(vertices, texture, ugli::DrawMode::Triangles);
+        geng.draw2d().draw2d(framebuffer, camera, &polygon);
+    }
+}
+
+/// Two triangles covering the area.
+fn quad(area: Aabb2<f32>, uv: Aabb2<f32>) -> [draw2d::TexturedVertex; 6] {
+    let positions = area.corners();
+    let uvs = uv.corners();
+    [0, 1, 2, 0, 2, 3].map(|i| draw2d::TexturedVertex {
+        a_pos: positions[i],
+        a_color: Rgba::WHITE,
+        a_vt: uvs[i],
+    })
+}
+
+#[test]
+fn test_nine_slice() {
+    let inside = |aabb: Aabb2<f32>, pos: vec2<f32>| {
+        (aabb.min.x..=aabb.max.x).contains(&pos.x) && (aabb.min.y..=aabb.max.y).contains(&pos.y)
+    };
+
+    let target = Aabb2::ZERO.extend_positive(vec2(30.0, 32.0));
+    let slice = DrawNineSlice::new(NineSliceBorders::uniform(4), target);
+
+    let vertices = slice.vertices(vec2(16, 16));
+    assert_eq!(vertices.len(), 9 * 6);
+
+    let vertices = slice
+        .fill_center(SliceFill::Tile)
+        .fill_edges(SliceFill::Tile)
+        .vertices(vec2(16, 16));
+    // 4 corners, 3 tiles per edge, 3x3 tiles in the center
+    assert_eq!(vertices.len(), (4 + 4 * 3 + 3 * 3) * 6);
+    for vertex in &vertices {
+        let pos = vertex.a_pos;
+        assert!(inside(target, pos), "{pos:?} is outside the target");
+        assert!(inside(
+            Aabb2::ZERO.extend_positive(vec2::splat(1.0)),
+            vertex.a_vt
+        ));
+    }
+
+    // The last tile is cut from 16 to 14 pixels
+    let vertices = DrawNineSlice::new(NineSliceBorders::uniform(0), target)
+        .fill_center(SliceFill::Tile)
+        .vertices(vec2(16, 16));
+    assert_eq!(vertices.len(), 2 * 2 * 6);
+    let max_uv = vertices.iter().map(|v| v.a_vt.x).fold(0.0, f32::max);
+    assert!((max_uv - 0.875).abs() < 1e-5);
+
+    // Borders are shrunk when the target is too small
+    let target = Aabb2::ZERO.extend_positive(vec2(4.0, 4.0));
+    let vertices = DrawNineSlice::new(NineSliceBorders::uniform(4), target).vertices(vec2(16, 16));
+    assert_eq!(vertices.len(), 4 * 6);
+    assert!(vertices.iter().all(|v| inside(target, v.a_pos)));
+}
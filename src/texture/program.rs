@@ -0,0 +1,79 @@
+use std::{cell::RefCell, rc::Rc};
+
+use geng::prelude::*;
+
+/// A program compiled for a specific context.
+/// Keeping the [Ugli] alive ensures that another context
+/// cannot be allocated at the same address.
+struct CachedProgram {
+    ugli: Ugli,
+    /// `None` if the compilation failed.
+    program: Option<Rc<ugli::Program>>,
+}
+
+thread_local! {
+    static TEXTURED_PROGRAMS: RefCell<Vec<CachedProgram>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The program used to draw textured geometry with custom [ugli::DrawParameters],
+/// since `draw2d` always draws with straight alpha blending.
+/// Compiled once per context and cached.
+/// Returns `None` if the program failed to compile, the error is logged once.
+fn textured_program(geng: &Geng) -> Option<Rc<ugli::Program>> {
+    let ugli = geng.ugli();
+    TEXTURED_PROGRAMS.with_borrow_mut(|programs| {
+        if let Some(cached) = programs
+            .iter()
+            .find(|cached| std::ptr::eq(cached.ugli.raw(), ugli.raw()))
+        {
+            return cached.program.clone();
+        }
+
+        let program = match geng.shader_lib().compile(include_str!("textured.glsl")) {
+            Ok(program) => Some(Rc::new(program)),
+            Err(err) => {
+                log::error!("Failed to compile the textured program: {err:?}");
+                None
+            }
+        };
+        programs.push(CachedProgram {
+            ugli: ugli.clone(),
+            program: program.clone(),
+        });
+        program
+    })
+}
+
+/// Draw textured geometry with the given parameters.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_textured(
+    vertices: &[draw2d::TexturedVertex],
+    mode: ugli::DrawMode,
+    texture: &ugli::Texture,
+    color: Rgba<f32>,
+    transform: mat3<f32>,
+    parameters: &ugli::DrawParameters,
+    camera: &impl geng::AbstractCamera2d,
+    geng: &Geng,
+    framebuffer: &mut ugli::Framebuffer,
+) {
+    let Some(program) = textured_program(geng) else {
+        return;
+    };
+    let vertices = ugli::VertexBuffer::new_dynamic(geng.ugli(), vertices.to_vec());
+    let framebuffer_size = framebuffer.size().map(|x| x as f32);
+    ugli::draw(
+        framebuffer,
+        &program,
+        mode,
+        &vertices,
+        ugli::uniforms! {
+            u_model_matrix: transform,
+            u_view_matrix: camera.view_matrix(),
+            u_projection_matrix: camera.projection_matrix(framebuffer_size),
+            u_texture: texture,
+            u_color: color,
+        },
+        parameters,
+    );
+}
@@ -0,0 +1,143 @@
+use geng::prelude::*;
+
+/// Read the contents of the framebuffer into an image.
+/// Rows are flipped, so that the top row of the framebuffer is the first row of the image.
+pub fn read_framebuffer(framebuffer: &ugli::FramebufferRead) -> image::RgbaImage {
+    let data = framebuffer.read_color();
+    image_from_bottom_up(framebuffer.size(), |x, y| data.get(x, y))
+}
+
+/// Read the contents of the texture into an image.
+/// Rows are flipped, so that the top row of the texture is the first row of the image.
+pub fn read_texture(texture: &ugli::Texture, ugli: &Ugli) -> image::RgbaImage {
+    let framebuffer =
+        ugli::FramebufferRead::new_color(ugli, ugli::ColorAttachmentRead::Texture(texture));
+    read_framebuffer(&framebuffer)
+}
+
+/// Construct an image from pixels stored bottom-up, as they are in OpenGL.
+pub fn image_from_bottom_up(
+    size: vec2<usize>,
+    get: impl Fn(usize, usize) -> Rgba<u8>,
+) -> image::RgbaImage {
+    let (width, height) = (size.x as u32, size.y as u32);
+    image::RgbaImage::from_fn(width, height, |x, y| {
+        let color = get(x as usize, (height - 1 - y) as usize);
+        image::Rgba([color.r, color.g, color.b, color.a])
+    })
+}
+
+/// Encode the image as PNG into an in-memory buffer.
+pub fn encode_png(image: &image::RgbaImage) -> anyhow::Result<Vec<u8>> {
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut buffer, image::ImageFormat::Png)
+        .context("when encoding png")?;
+    Ok(buffer.into_inner())
+}
+
+/// Capture a screenshot and save it as a PNG file in the given directory,
+/// named `{prefix}_YYYY-MM-DD_HH-MM-SS.png` using the current UTC time.
+/// If a file with that name already exists, a number is appended: `..._HH-MM-SS_2.png`.
+/// Returns the path to the saved file.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_screenshot(
+    framebuffer: &ugli::FramebufferRead,
+    dir: impl AsRef<std::path::Path>,
+    prefix: &str,
+) -> anyhow::Result<std::path::PathBuf> {
+    use std::io::Write;
+
+    let time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .context("system time is before the unix epoch")?;
+    let png = encode_png(&read_framebuffer(framebuffer))?;
+
+    let mut index = 0;
+    loop {
+        index += 1;
+        let name = numbered_name(&timestamped_name(prefix, time.as_secs(), "png"), index);
+        let path = dir.as_ref().join(name);
+        // Never overwrite existing screenshots
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path);
+        let mut file = match file {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("when creating screenshot {path:?}"));
+            }
+        };
+        file.write_all(&png)
+            .with_context(|| format!("when saving screenshot to {path:?}"))?;
+        return Ok(path);
+    }
+}
+
+/// Append `_{index}` to the file name before the extension, unless the index is 1.
+#[cfg(not(target_arch = "wasm32"))]
+fn numbered_name(name: &str, index: usize) -> String {
+    if index <= 1 {
+        return name.to_owned();
+    }
+    match name.rsplit_once('.') {
+        Some((stem, extension)) => format!("{stem}_{index}.{extension}"),
+        None => format!("{name}_{index}"),
+    }
+}
+
+/// Format a file name as `{prefix}_YYYY-MM-DD_HH-MM-SS.{extension}`
+/// from the number of seconds since the unix epoch (UTC).
+pub fn timestamped_name(prefix: &str, unix_secs: u64, extension: &str) -> String {
+    let days = (unix_secs / 86400) as i64;
+    let secs = unix_secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{prefix}_{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}.{extension}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Convert the number of days since the unix epoch into a (year, month, day) date.
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097); // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365; // [0, 399]
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11]
+    let day = doy - (153 * mp + 2) / 5 + 1; // [1, 31]
+    let month = if mp < 10 { mp + 3 } else { mp - 9 }; // [1, 12]
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month as u32, day as u32)
+}
+
+#[test]
+fn test_readback_helpers() {
+    assert_eq!(
+        timestamped_name("screenshot", 0, "png"),
+        "screenshot_1970-01-01_00-00-00.png"
+    );
+    assert_eq!(
+        timestamped_name("shot", 1_700_000_000, "png"),
+        "shot_2023-11-14_22-13-20.png"
+    );
+    assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    assert_eq!(numbered_name("shot.png", 1), "shot.png");
+    assert_eq!(numbered_name("shot.png", 3), "shot_3.png");
+
+    // Bottom row in GL is the last row of the image
+    let image = image_from_bottom_up(vec2(2, 2), |x, y| Rgba {
+        r: x as u8,
+        g: y as u8,
+        b: 0,
+        a: 255,
+    });
+    assert_eq!(image.get_pixel(1, 0).0, [1, 1, 0, 255]);
+    assert_eq!(image.get_pixel(0, 1).0, [0, 0, 0, 255]);
+}
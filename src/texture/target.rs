@@ -0,0 +1,224 @@
+use geng::prelude::*;
+
+use super::RenderTargetOptions;
+
+/// Format of the depth buffer attached to a [RenderTarget].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepthFormat {
+    /// No depth buffer, color only.
+    None,
+    /// Depth buffer.
+    Depth,
+    /// Combined depth and stencil buffer.
+    DepthStencil,
+}
+
+enum DepthBuffer {
+    Depth(ugli::Renderbuffer<ugli::DepthComponent>),
+    DepthStencil(ugli::Renderbuffer<ugli::DepthStencilValue>),
+}
+
+impl DepthBuffer {
+    fn new(ugli: &Ugli, size: vec2<usize>, format: DepthFormat) -> Option<Self> {
+        match format {
+            DepthFormat::None => None,
+            DepthFormat::Depth => Some(Self::Depth(ugli::Renderbuffer::new(ugli, size))),
+            DepthFormat::DepthStencil => {
+                Some(Self::DepthStencil(ugli::Renderbuffer::new(ugli, size)))
+            }
+        }
+    }
+}
+
+/// An offscreen render target: a color texture with an optional depth (and stencil) buffer.
+pub struct RenderTarget {
+    /// The color texture. Can be drawn after rendering into the target.
+    pub color: ugli::Texture,
+    depth: Option<DepthBuffer>,
+    depth_format: DepthFormat,
+    options: RenderTargetOptions,
+}
+
+impl RenderTarget {
+    pub fn new(
+        ugli: &Ugli,
+        size: vec2<usize>,
+        depth_format: DepthFormat,
+        options: RenderTargetOptions,
+    ) -> Self {
+        Self {
+            color: super::new_texture_with(ugli, size, &options),
+            depth: DepthBuffer::new(ugli, size, depth_format),
+            depth_format,
+            options,
+        }
+    }
+
+    pub fn size(&self) -> vec2<usize> {
+        self.color.size()
+    }
+
+    pub fn depth_format(&self) -> DepthFormat {
+        self.depth_format
+    }
+
+    pub fn options(&self) -> &RenderTargetOptions {
+        &self.options
+    }
+
+    /// Ensure the target's size is equal to `size`.
+    /// If the size is the same, the target doesn't change.
+    /// The depth buffer is always cleared when resized.
+    pub fn update_size(&mut self, size: vec2<usize>, geng: &Geng) {
+        if self.size() == size {
+            return;
+        }
+        super::update_texture_size_with(&mut self.color, size, &self.options, geng);
+        self.depth = DepthBuffer::new(geng.ugli(), size, self.depth_format);
+    }
+
+    /// A framebuffer to render into the target.
+    pub fn framebuffer(&mut self, ugli: &Ugli) -> ugli::Framebuffer<'_> {
+        let color = ugli::ColorAttachment::Texture(&mut self.color);
+        let depth = match &mut self.depth {
+            None => ugli::DepthAttachment::None,
+            Some(DepthBuffer::Depth(buffer)) => ugli::DepthAttachment::Renderbuffer(buffer),
+            Some(DepthBuffer::DepthStencil(buffer)) => {
+                ugli::DepthAttachment::RenderbufferWithStencil(buffer)
+            }
+        };
+        ugli::Framebuffer::new(ugli, color, depth)
+    }
+}
+
+/// A pool of temporary [RenderTarget]s, reused across frames for multi-pass effects.
+///
+/// Take a target with [RenderTargetPool::get], and give it back with [RenderTargetPool::release]
+/// when it is no longer needed. Call [RenderTargetPool::end_frame] once per frame
+/// to free targets that have not been used for a while.
+pub struct RenderTargetPool {
+    pool: Pool<(vec2<usize>, DepthFormat), RenderTarget>,
+    options: RenderTargetOptions,
+}
+
+impl RenderTargetPool {
+    /// Create a pool of targets with the given options,
+    /// where targets are freed after staying unused for `max_unused_frames`.
+    pub fn new(options: RenderTargetOptions, max_unused_frames: u64) -> Self {
+        Self {
+            pool: Pool::new(max_unused_frames),
+            options,
+        }
+    }
+
+    /// Take a target of the given size and format from the pool, or create a new one.
+    /// The contents of a reused target are not cleared.
+    pub fn get(
+        &mut self,
+        ugli: &Ugli,
+        size: vec2<usize>,
+        depth_format: DepthFormat,
+    ) -> RenderTarget {
+        self.pool
+            .take(&(size, depth_format))
+            .unwrap_or_else(|| RenderTarget::new(ugli, size, depth_format, self.options))
+    }
+
+    /// Return the target into the pool.
+    pub fn release(&mut self, target: RenderTarget) {
+        self.pool.put((target.size(), target.depth_format), target);
+    }
+
+    /// Advance to the next frame, freeing the targets that were not used recently.
+    pub fn end_frame(&mut self) {
+        self.pool.end_frame();
+    }
+
+    /// Number of targets currently available in the pool.
+    pub fn len(&self) -> usize {
+        self.pool.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pool.free.is_empty()
+    }
+}
+
+struct Pooled<K, T> {
+    key: K,
+    item: T,
+    last_used: u64,
+}
+
+/// Free items grouped by key, freed after some number of frames without use.
+struct Pool<K, T> {
+    free: Vec<Pooled<K, T>>,
+    frame: u64,
+    max_unused_frames: u64,
+}
+
+impl<K: PartialEq, T> Pool<K, T> {
+    fn new(max_unused_frames: u64) -> Self {
+        Self {
+            free: Vec::new(),
+            frame: 0,
+            max_unused_frames,
+        }
+    }
+
+    fn take(&mut self, key: &K) -> Option<T> {
+        // Items are kept in the order they were put in, so take the most recently used one
+        let index = self.free.iter().rposition(|pooled| pooled.key == *key)?;
+        Some(self.free.remove(index).item)
+    }
+
+    fn put(&mut self, key: K, item: T) {
+        self.free.push(Pooled {
+            key,
+            item,
+            last_used: self.frame,
+        });
+    }
+
+    fn end_frame(&mut self) {
+        self.frame += 1;
+        let (frame, max) = (self.frame, self.max_unused_frames);
+        self.free.retain(|pooled| frame - pooled.last_used <= max);
+    }
+}
+
+#[test]
+fn test_pool() {
+    let mut pool = Pool::new(1);
+    assert_eq!(pool.take(&"a"), None);
+
+    pool.put("a", 1);
+    pool.put("b", 2);
+    assert_eq!(pool.take(&"a"), Some(1));
+    assert_eq!(pool.take(&"a"), None);
+
+    pool.put("a", 1);
+    pool.end_frame();
+    // Still alive after one frame
+    assert_eq!(pool.take(&"a"), Some(1));
+    pool.put("a", 1);
+    pool.end_frame();
+    pool.end_frame();
+    // Freed after two unused frames
+    assert_eq!(pool.take(&"a"), None);
+    assert_eq!(pool.take(&"b"), None);
+
+    // Several items with the same key are taken from the most recently used one
+    pool.put("a", 1);
+    pool.put("b", 2);
+    pool.put("a", 3);
+    pool.put("c", 4);
+    pool.put("a", 5);
+    assert_eq!(pool.take(&"a"), Some(5));
+    assert_eq!(pool.take(&"a"), Some(3));
+    pool.put("a", 6);
+    assert_eq!(pool.take(&"a"), Some(6));
+    assert_eq!(pool.take(&"a"), Some(1));
+    assert_eq!(pool.take(&"a"), None);
+    assert_eq!(pool.free.len(), 2);
+}
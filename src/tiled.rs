@@ -36,9 +36,15 @@ pub fn tile_area_subtexture<T: Float>(
     (0..tiles.x)
         .flat_map(|x| {
             (0..tiles.y).flat_map(move |y| {
-                let vs = unit.map(|v| draw2d::TexturedVertex {
-                    a_pos: area.bottom_left() + offset + vec2(x, y).as_f32() * tile_size,
-                    ..v
+                let vs = unit.map(|v| {
+                    // Unit quad is in range -1..=1
+                    let corner = (v.a_pos + vec2::splat(1.0)) / 2.0;
+                    draw2d::TexturedVertex {
+                        a_pos: area.bottom_left()
+                            + offset
+                            + (vec2(x, y).as_f32() + corner) * tile_size,
+                        ..v
+                    }
                 });
                 let indices = [0, 1, 2, 0, 2, 3];
                 indices.map(|i| vs[i])
@@ -46,3 +52,135 @@ pub fn tile_area_subtexture<T: Float>(
         })
         .collect()
 }
+
+/// Tiles a target of the given size, returning triangles in the unit quad space (`-1..=1`),
+/// which can then be transformed the same way as [crate::geometry::unit_quad].
+/// Tiles are shifted by `offset` from the bottom-left corner of the target,
+/// and the tiles on the edges are cut off.
+pub fn tile_unit_quad(
+    sub_texture: Aabb2<f32>,
+    tile_size: vec2<f32>,
+    offset: vec2<f32>,
+    target_size: vec2<f32>,
+) -> Vec<draw2d::TexturedVertex> {
+    let half = target_size / 2.0;
+    if tile_size.x <= 0.0 || tile_size.y <= 0.0 || half.x <= 0.0 || half.y <= 0.0 {
+        return Vec::new();
+    }
+
+    // Tile the target in its local space, starting before the bottom-left corner
+    // so that the offset tiles still cover the whole target
+    let area = Aabb2 {
+        min: -half,
+        max: half,
+    };
+    let start = tiles_start(offset, tile_size);
+    let tiles = tile_area_subtexture(
+        sub_texture,
+        tile_size,
+        vec2::ZERO,
+        Aabb2 {
+            min: area.min + start,
+            max: area.max,
+        },
+    );
+    clip_tiles(&tiles, area)
+        .into_iter()
+        .map(|vertex| draw2d::TexturedVertex {
+            a_pos: vertex.a_pos / half,
+            ..vertex
+        })
+        .collect()
+}
+
+/// Position of the first tile relative to the bottom-left corner,
+/// wrapping the offset into the range `-tile_size..=0`.
+fn tiles_start(offset: vec2<f32>, tile_size: vec2<f32>) -> vec2<f32> {
+    let shift = |offset: f32, size: f32| {
+        let offset = offset.rem_euclid(size);
+        if offset > 0.0 { offset - size } else { 0.0 }
+    };
+    vec2(shift(offset.x, tile_size.x), shift(offset.y, tile_size.y))
+}
+
+/// Cut off the parts of the tiles outside the area, adjusting the uv's.
+/// Expects tiles as generated by [tile_area_subtexture].
+pub fn clip_tiles(
+    tiles: &[draw2d::TexturedVertex],
+    area: Aabb2<f32>,
+) -> Vec<draw2d::TexturedVertex> {
+    tiles
+        .chunks_exact(6)
+        .flat_map(|tile| {
+            // Bottom-left and top-right corners
+            let (pos_min, pos_max) = (tile[0].a_pos, tile[2].a_pos);
+            let (uv_min, uv_max) = (tile[0].a_vt, tile[2].a_vt);
+            tile.iter().map(move |vertex| {
+                let pos = vec2(
+                    vertex.a_pos.x.clamp(area.min.x, area.max.x),
+                    vertex.a_pos.y.clamp(area.min.y, area.max.y),
+                );
+                let t = (pos - pos_min) / (pos_max - pos_min);
+                draw2d::TexturedVertex {
+                    a_pos: pos,
+                    a_vt: uv_min + (uv_max - uv_min) * t,
+                    ..*vertex
+                }
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_tile_unit_quad() {
+    let sub_texture = Aabb2 {
+        min: vec2(0.25, 0.5),
+        max: vec2(0.75, 1.0),
+    };
+    let tile_size = vec2(4.0, 4.0);
+    let target_size = vec2(10.0, 6.0);
+
+    let tiles = |offset| tile_unit_quad(sub_texture, tile_size, offset, target_size);
+    assert_eq!(tiles(vec2::ZERO).len(), 3 * 2 * 6);
+    // A full tile offset is the same as none
+    assert_eq!(tiles(vec2(8.0, -4.0)), tiles(vec2::ZERO));
+
+    for (offset, count) in [
+        (vec2(0.0, 0.0), 3 * 2),
+        (vec2(1.0, 1.0), 4 * 3),
+        (vec2(-1.0, -1.0), 3 * 2),
+        (vec2(-1.0, 0.0), 3 * 2),
+        (vec2(8.0, 4.0), 3 * 2),
+    ] {
+        let vertices = tiles(offset);
+        assert_eq!(vertices.len(), count * 6, "offset {offset:?}");
+
+        let start = tiles_start(offset, tile_size);
+        for tile in vertices.chunks_exact(6) {
+            // Find which tile this is from its center
+            let center = (tile[0].a_pos + tile[2].a_pos) / 2.0;
+            let center = (center + vec2::splat(1.0)) / 2.0 * target_size;
+            let index = ((center - start) / tile_size).map(f32::floor);
+            let tile_min = start + index * tile_size;
+
+            for vertex in tile {
+                let pos = vertex.a_pos;
+                let inside = |x: f32| (-1.0 - 1e-5..=1.0 + 1e-5).contains(&x);
+                assert!(inside(pos.x) && inside(pos.y), "{pos:?} is outside");
+
+                // Uv's are interpolated from the position inside of the tile
+                let pos = (pos + vec2::splat(1.0)) / 2.0 * target_size;
+                let t = (pos - tile_min) / tile_size;
+                let uv = sub_texture.min + t * sub_texture.size();
+                let error = (uv - vertex.a_vt).map(f32::abs);
+                assert!(
+                    error.x < 1e-5 && error.y < 1e-5,
+                    "{uv:?} != {:?}",
+                    vertex.a_vt
+                );
+            }
+        }
+    }
+
+    assert!(tile_unit_quad(sub_texture, vec2::ZERO, vec2::ZERO, target_size).is_empty());
+}